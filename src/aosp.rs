@@ -0,0 +1,379 @@
+use crate::xml::{self, Element};
+use crate::{ImportError, Key, KeyLayout, KeyShape};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// Height of a row, which is the same as the height of the keys of `get_default_layout()`
+const ROW_HEIGHT: f64 = 0.1;
+// Width of a key if neither the key nor its row or keyboard define one
+const DEFAULT_KEY_WIDTH: f64 = 0.1;
+// Includes can't be nested deeper, so files including each other are detected
+const MAX_INCLUDE_DEPTH: usize = 16;
+// Includes can't be expanded more often, so files including each other many times can't take forever
+const MAX_INCLUDES: usize = 1000;
+
+impl KeyLayout {
+    /// Import a keyboard layout definition of the AOSP keyboard (LatinIME)
+    ///
+    /// The coordinates are normalized like `get_default_layout()`: the width of the keyboard is 1.0 and each row
+    /// is 0.1 high, as the heights in the files depend on the device. Key widths and positions (`keyXPos`) are
+    /// given in percent of the keyboard width and a `horizontalGap` is taken off both sides of the keys.
+    /// `Spacer` elements, key styles and the default case of `switch` elements are supported.
+    ///
+    /// A key produces the output of its `keySpec`, or the character of its `keyLabel` or first `codes` entry in the
+    /// older format, if that is a single character.
+    pub fn from_aosp_xml(name: &str, source: &str) -> Result<Self, ImportError> {
+        Self::from_aosp_xml_with_includes(name, source, &HashMap::new())
+    }
+
+    /// Import a keyboard layout definition of the AOSP keyboard that includes other files
+    /// The included files are looked up by their resource name, e.g. "rowkeys_qwerty1" for "@xml/rowkeys_qwerty1".
+    pub fn from_aosp_xml_with_includes(
+        name: &str,
+        source: &str,
+        includes: &HashMap<String, String>,
+    ) -> Result<Self, ImportError> {
+        let mut importer = Importer {
+            includes,
+            styles: HashMap::new(),
+            key_width: DEFAULT_KEY_WIDTH,
+            horizontal_gap: 0.0,
+            keys: Vec::new(),
+            no_rows: 0,
+            include_depth: 0,
+            parsed_includes: HashMap::new(),
+            no_includes: 0,
+        };
+        importer.import(&xml::parse(source)?, &mut None)?;
+        Ok(Self::new(name, importer.keys)?)
+    }
+}
+
+// Position of the next key in the current row
+struct RowState {
+    x: f64,
+    key_width: f64,
+    horizontal_gap: f64,
+}
+
+struct Importer<'i> {
+    includes: &'i HashMap<String, String>,
+    // Attributes of the key styles by their names
+    styles: HashMap<String, Vec<(String, String)>>,
+    // Defaults of the keyboard
+    key_width: f64,
+    horizontal_gap: f64,
+    keys: Vec<Key>,
+    no_rows: usize,
+    include_depth: usize,
+    // Included files by their resource name, so each one is only parsed once
+    parsed_includes: HashMap<String, Rc<Element>>,
+    // Number of includes that were expanded so far
+    no_includes: usize,
+}
+
+impl Importer<'_> {
+    fn import(&mut self, element: &Element, row: &mut Option<RowState>) -> Result<(), ImportError> {
+        match element.local_name() {
+            "Keyboard" => {
+                if let Some(key_width) = element.attribute("keyWidth") {
+                    self.key_width = width(element, "keyWidth", key_width, 0.0)?;
+                }
+                if let Some(horizontal_gap) = element.attribute("horizontalGap") {
+                    self.horizontal_gap = fraction(element, "horizontalGap", horizontal_gap)?;
+                }
+                self.import_children(element, row)
+            }
+            "merge" => self.import_children(element, row),
+            "Row" => {
+                if row.is_some() {
+                    return Err(invalid_element(element, "inside of a Row"));
+                }
+                let mut row_state = Some(RowState {
+                    x: 0.0,
+                    key_width: match element.attribute("keyWidth") {
+                        Some(key_width) => width(element, "keyWidth", key_width, 0.0)?,
+                        None => self.key_width,
+                    },
+                    horizontal_gap: match element.attribute("horizontalGap") {
+                        Some(horizontal_gap) => fraction(element, "horizontalGap", horizontal_gap)?,
+                        None => self.horizontal_gap,
+                    },
+                });
+                self.import_children(element, &mut row_state)?;
+                self.no_rows += 1;
+                Ok(())
+            }
+            "Key" | "Spacer" => {
+                let row = row
+                    .as_mut()
+                    .ok_or_else(|| invalid_element(element, "outside of a Row"))?;
+                self.import_key(element, row)
+            }
+            "include" => self.import_include(element, row),
+            "switch" => {
+                // The conditions of the cases depend on the device, so the default case is used
+                let branch = element
+                    .children
+                    .iter()
+                    .find(|child| child.local_name() == "default")
+                    .or_else(|| {
+                        element
+                            .children
+                            .iter()
+                            .find(|child| child.local_name() == "case")
+                    });
+                match branch {
+                    Some(branch) => self.import_children(branch, row),
+                    None => Ok(()),
+                }
+            }
+            "key-style" => {
+                let style_name = element
+                    .attribute("styleName")
+                    .ok_or_else(|| missing_attribute(element, "styleName"))?;
+                self.styles
+                    .insert(style_name.to_string(), element.attributes.clone());
+                Ok(())
+            }
+            // Other elements don't contain keys
+            _ => Ok(()),
+        }
+    }
+
+    fn import_children(
+        &mut self,
+        element: &Element,
+        row: &mut Option<RowState>,
+    ) -> Result<(), ImportError> {
+        for child in &element.children {
+            self.import(child, row)?;
+        }
+        Ok(())
+    }
+
+    fn import_include(
+        &mut self,
+        element: &Element,
+        row: &mut Option<RowState>,
+    ) -> Result<(), ImportError> {
+        let reference = element
+            .attribute("keyboardLayout")
+            .ok_or_else(|| missing_attribute(element, "keyboardLayout"))?;
+        let resource = reference.strip_prefix("@xml/").unwrap_or(reference);
+        let source = self
+            .includes
+            .get(resource)
+            .ok_or_else(|| invalid_attribute(element, "keyboardLayout", reference))?;
+        if self.include_depth >= MAX_INCLUDE_DEPTH || self.no_includes >= MAX_INCLUDES {
+            return Err(invalid_attribute(element, "keyboardLayout", reference));
+        }
+        self.no_includes += 1;
+        let included = match self.parsed_includes.get(resource) {
+            Some(included) => Rc::clone(included),
+            None => {
+                let included = Rc::new(xml::parse(source)?);
+                self.parsed_includes
+                    .insert(resource.to_string(), Rc::clone(&included));
+                included
+            }
+        };
+
+        // The key position and width of an include apply to the included keys
+        let mut previous_key_width = None;
+        if let Some(row) = row.as_mut() {
+            if let Some(key_x_pos) = element.attribute("keyXPos") {
+                row.x = position(element, key_x_pos)?;
+            }
+            if let Some(key_width) = element.attribute("keyWidth") {
+                previous_key_width = Some(row.key_width);
+                row.key_width = width(element, "keyWidth", key_width, row.x)?;
+            }
+        }
+        self.include_depth += 1;
+        let result = self.import(&included, row);
+        self.include_depth -= 1;
+        if let (Some(row), Some(key_width)) = (row.as_mut(), previous_key_width) {
+            row.key_width = key_width;
+        }
+        result
+    }
+
+    fn import_key(&mut self, element: &Element, row: &mut RowState) -> Result<(), ImportError> {
+        if let Some(key_x_pos) = self.key_attribute(element, "keyXPos") {
+            row.x = position(element, key_x_pos)?;
+        }
+        let key_width = match self.key_attribute(element, "keyWidth") {
+            Some(key_width) => width(element, "keyWidth", key_width, row.x)?,
+            None => row.key_width,
+        };
+        let x = row.x;
+        row.x += key_width;
+        if element.local_name() == "Spacer" {
+            return Ok(());
+        }
+
+        let (label, chars) = self.key_text(element);
+        let mut key = Key::from_shape(
+            &label,
+            KeyShape::Rect {
+                x: x + row.horizontal_gap / 2.0,
+                y: self.no_rows as f64 * ROW_HEIGHT,
+                width: key_width - row.horizontal_gap,
+                height: ROW_HEIGHT,
+            },
+        );
+        key.chars = chars;
+        self.keys.push(key);
+        Ok(())
+    }
+
+    // Get an attribute of a key, which may be inherited from its style
+    fn key_attribute<'e>(&'e self, element: &'e Element, name: &str) -> Option<&'e str> {
+        if let Some(value) = element.attribute(name) {
+            return Some(value);
+        }
+        let mut style_name = element.attribute("keyStyle");
+        // Limit the chain of parent styles in case that styles are each others parents
+        for _ in 0..self.styles.len() {
+            let style = self.styles.get(style_name?)?;
+            let attribute = |name: &str| {
+                style
+                    .iter()
+                    .find(|(attribute, _)| attribute.rsplit(':').next() == Some(name))
+                    .map(|(_, value)| value.as_str())
+            };
+            if let Some(value) = attribute(name) {
+                return Some(value);
+            }
+            style_name = attribute("parentStyle");
+        }
+        None
+    }
+
+    // Get the label of a key and the characters it produces
+    fn key_text(&self, element: &Element) -> (String, Vec<char>) {
+        let single_char = |text: &str| -> Vec<char> {
+            let mut chars = text.chars();
+            match (chars.next(), chars.next()) {
+                (Some(character), None) => vec![character],
+                _ => Vec::new(),
+            }
+        };
+
+        if let Some(key_spec) = self.key_attribute(element, "keySpec") {
+            // The key spec is either the label that is also the output or the label and the output separated by '|'
+            let (label, output) = split_key_spec(key_spec);
+            let output = output.unwrap_or_else(|| label.clone());
+            let chars = if output.starts_with('!') {
+                Vec::new()
+            } else {
+                single_char(&output)
+            };
+            let label = match label.strip_prefix("!icon/") {
+                Some(icon) => icon.to_string(),
+                None => label,
+            };
+            return (label, chars);
+        }
+
+        if let Some(key_label) = self.key_attribute(element, "keyLabel") {
+            let code = self
+                .key_attribute(element, "codes")
+                .and_then(|codes| codes.split(',').next())
+                .and_then(|code| code.trim().parse::<u32>().ok())
+                .and_then(char::from_u32);
+            let chars = match code {
+                Some(character) => vec![character],
+                None => single_char(key_label),
+            };
+            return (key_label.to_string(), chars);
+        }
+
+        // Keys like the shift key only have a style
+        let label = element
+            .attribute("keyStyle")
+            .map(|style| style.strip_suffix("KeyStyle").unwrap_or(style))
+            .unwrap_or_default();
+        (label.to_string(), Vec::new())
+    }
+}
+
+// Split a key spec into its label and its output, which are separated by an unescaped '|'
+fn split_key_spec(key_spec: &str) -> (String, Option<String>) {
+    let mut label = String::new();
+    let mut chars = key_spec.chars();
+    while let Some(character) = chars.next() {
+        match character {
+            '\\' => label.extend(chars.next()),
+            '|' => {
+                let output = chars.as_str().replace('\\', "");
+                return (label, Some(output));
+            }
+            character => label.push(character),
+        }
+    }
+    (label, None)
+}
+
+// Parse a length in percent of the keyboard width, e.g. "10%p", into a fraction of the keyboard width
+// Absolute lengths depend on the device, so they are only accepted if they are zero, e.g. a gap of "0px"
+fn fraction(element: &Element, attribute: &str, value: &str) -> Result<f64, ImportError> {
+    let is_zero = ["px", "dip", "dp"]
+        .iter()
+        .find_map(|unit| value.strip_suffix(unit))
+        .and_then(|length| length.trim().parse::<f64>().ok())
+        == Some(0.0);
+    if is_zero {
+        return Ok(0.0);
+    }
+    value
+        .strip_suffix("%p")
+        .or_else(|| value.strip_suffix('%'))
+        .and_then(|percent| percent.trim().parse::<f64>().ok())
+        .filter(|percent| percent.is_finite())
+        .map(|percent| percent / 100.0)
+        .ok_or_else(|| invalid_attribute(element, attribute, value))
+}
+
+// Parse the position of a key, which is measured from the right edge if it is negative
+fn position(element: &Element, value: &str) -> Result<f64, ImportError> {
+    let x = fraction(element, "keyXPos", value)?;
+    Ok(if x < 0.0 { 1.0 + x } else { x })
+}
+
+// Parse the width of a key, which can also fill the row up to the right edge
+fn width(element: &Element, attribute: &str, value: &str, x: f64) -> Result<f64, ImportError> {
+    match value {
+        "fillRight" | "fillBoth" => Ok((1.0 - x).max(0.0)),
+        _ => fraction(element, attribute, value),
+    }
+}
+
+fn invalid_element(element: &Element, value: &str) -> ImportError {
+    ImportError::invalid_value(
+        &format!("{} at line {}", element.local_name(), element.line),
+        value,
+    )
+}
+
+fn invalid_attribute(element: &Element, attribute: &str, value: &str) -> ImportError {
+    ImportError::invalid_value(
+        &format!(
+            "{}.{} at line {}",
+            element.local_name(),
+            attribute,
+            element.line
+        ),
+        value,
+    )
+}
+
+fn missing_attribute(element: &Element, attribute: &str) -> ImportError {
+    ImportError::missing_field(&format!(
+        "{}.{} at line {}",
+        element.local_name(),
+        attribute,
+        element.line
+    ))
+}
@@ -0,0 +1,36 @@
+use crate::{KeyLayout, RowLayoutOptions};
+
+// Names of the built-in layouts with the characters of their rows from top to bottom
+const BUILTIN_LAYOUTS: &[(&str, &[&str])] = &[
+    ("qwerty", &["qwertyuiop", "asdfghjkl", "zxcvbnm"]),
+    // German
+    ("qwertz", &["qwertzuiopü", "asdfghjklöä", "yxcvbnm"]),
+    // French
+    ("azerty", &["azertyuiop", "qsdfghjklm", "wxcvbn"]),
+    ("dvorak", &["',.pyfgcrl", "aoeuidhtns", ";qjkxbmwvz"]),
+    ("colemak", &["qwfpgjluy", "arstdhneio", "zxcvbkm"]),
+    ("workman", &["qdrwbjfup", "ashtgyneoi", "zxmcvkl"]),
+    // Swedish and Finnish
+    ("nordic", &["qwertyuiopå", "asdfghjklöä", "zxcvbnm"]),
+    // Russian
+    ("jcuken", &["йцукенгшщзхъ", "фывапролджэ", "ячсмитьбю"]),
+    ("greek", &[";ςερτυθιοπ", "ασδφγηξκλ", "ζχψωβνμ"]),
+];
+
+/// Get the names of the built-in layouts that can be passed to `get_builtin_layout()`
+pub fn builtin_layout_names() -> Vec<&'static str> {
+    BUILTIN_LAYOUTS.iter().map(|(name, _)| *name).collect()
+}
+
+/// Get a built-in layout by its name, e.g. "qwertz", "azerty", "dvorak" or "jcuken"
+///
+/// The layouts only contain the keys of the characters, which are squares with a side length of 0.1 like in
+/// `get_default_layout()`. Each row is staggered by half a key, so rows with more than ten keys are wider than 1.0.
+/// None is returned if there is no layout with the name.
+pub fn get_builtin_layout(name: &str) -> Option<KeyLayout> {
+    let (name, rows) = BUILTIN_LAYOUTS
+        .iter()
+        .find(|(layout_name, _)| *layout_name == name)?;
+    let key_layout = KeyLayout::from_rows(name, rows, &RowLayoutOptions::default());
+    Some(key_layout.expect("the built-in layouts are valid"))
+}
@@ -0,0 +1,107 @@
+use crate::{dist, Point};
+
+/// Number of points used to approximate one leg of a curve when measuring its arc length
+pub(crate) const SAMPLES_PER_LEG: usize = 64;
+
+/// Exponent of the knot parametrization. 0.5 results in a centripetal Catmull-Rom spline,
+/// which neither forms cusps nor self-intersects within a leg
+const ALPHA: f64 = 0.5;
+
+/// A polyline together with the cumulative length at each of its points
+/// It is used to find points that are a given arc length away from the start
+pub(crate) struct ArcLengthTable {
+    points: Vec<Point>,
+    lengths: Vec<f64>,
+}
+
+impl ArcLengthTable {
+    pub(crate) fn new(points: Vec<Point>) -> Self {
+        let mut lengths = Vec::with_capacity(points.len());
+        let mut total_length = 0.0;
+        for (idx, point) in points.iter().enumerate() {
+            if idx > 0 {
+                total_length += dist(&points[idx - 1], point);
+            }
+            lengths.push(total_length);
+        }
+        Self { points, lengths }
+    }
+
+    /// Total length of the polyline
+    pub(crate) fn length(&self) -> f64 {
+        self.lengths.last().copied().unwrap_or(0.0)
+    }
+
+    /// Get the point that is the arc length `s` away from the start of the polyline
+    pub(crate) fn point_at(&self, s: f64) -> Point {
+        // Find the first point that is further away than s
+        let idx = self.lengths.partition_point(|&length| length < s);
+        if idx == 0 {
+            return self.points[0];
+        }
+        if idx >= self.points.len() {
+            return self.points[self.points.len() - 1];
+        }
+        let (start, end) = (self.points[idx - 1], self.points[idx]);
+        let section_length = self.lengths[idx] - self.lengths[idx - 1];
+        if section_length <= 0.0 {
+            return end;
+        }
+        let t = (s - self.lengths[idx - 1]) / section_length;
+        (
+            start.0 + (end.0 - start.0) * t,
+            start.1 + (end.1 - start.1) * t,
+        )
+    }
+}
+
+/// Sample the leg from waypoints[leg] to waypoints[leg + 1] of a centripetal Catmull-Rom spline through all waypoints
+/// The returned points include both ends of the leg
+pub(crate) fn catmull_rom_leg(waypoints: &[Point], leg: usize, samples: usize) -> Vec<Point> {
+    let p1 = waypoints[leg];
+    let p2 = waypoints[leg + 1];
+    // A zero length leg can't be curved
+    if dist(&p1, &p2) < 0.0000001 {
+        return vec![p1, p2];
+    }
+    // The first and the last leg don't have a neighbour on one side. The same is true if the neighbour is on top of the
+    // end of the leg. In these cases the other end of the leg is mirrored to get a control point
+    let p0 = match leg.checked_sub(1).map(|idx| waypoints[idx]) {
+        Some(p0) if dist(&p0, &p1) >= 0.0000001 => p0,
+        _ => (2.0 * p1.0 - p2.0, 2.0 * p1.1 - p2.1),
+    };
+    let p3 = match waypoints.get(leg + 2) {
+        Some(&p3) if dist(&p2, &p3) >= 0.0000001 => p3,
+        _ => (2.0 * p2.0 - p1.0, 2.0 * p2.1 - p1.1),
+    };
+
+    let t0 = 0.0;
+    let t1 = t0 + dist(&p0, &p1).powf(ALPHA);
+    let t2 = t1 + dist(&p1, &p2).powf(ALPHA);
+    let t3 = t2 + dist(&p2, &p3).powf(ALPHA);
+
+    let mut points = Vec::with_capacity(samples + 1);
+    points.push(p1);
+    for i in 1..samples {
+        let t = t1 + (t2 - t1) * i as f64 / samples as f64;
+        // Barry and Goldman's pyramidal formulation
+        let a1 = lerp(p0, p1, t0, t1, t);
+        let a2 = lerp(p1, p2, t1, t2, t);
+        let a3 = lerp(p2, p3, t2, t3, t);
+        let b1 = lerp(a1, a2, t0, t2, t);
+        let b2 = lerp(a2, a3, t1, t3, t);
+        points.push(lerp(b1, b2, t1, t2, t));
+    }
+    points.push(p2);
+    points
+}
+
+// Linear interpolation between the points a and b, which are at the parameters t_a and t_b
+fn lerp(a: Point, b: Point, t_a: f64, t_b: f64, t: f64) -> Point {
+    let weight_a = (t_b - t) / (t_b - t_a);
+    let weight_b = (t - t_a) / (t_b - t_a);
+    (
+        weight_a * a.0 + weight_b * b.0,
+        weight_a * a.1 + weight_b * b.1,
+    )
+}
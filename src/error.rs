@@ -0,0 +1,228 @@
+use std::fmt;
+
+/// Reasons why no path can be generated for a word
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathError {
+    /// The word does not contain any letters
+    EmptyWord,
+    /// The layout has no key for a character of the word
+    MissingKey {
+        /// The character without a key
+        character: char,
+        /// Index of the character in the word that was passed to the WordPath
+        index: usize,
+    },
+    /// The desired point density is not a positive number
+    InvalidPointDensity(f64),
+    /// The sample interval of a timed path is not a positive number
+    InvalidSampleInterval(f64),
+    /// A path can't be resampled to the requested number of points
+    InvalidPointCount(usize),
+    /// The path that is compared with the word has no points
+    EmptyUserPath,
+    /// A leg of the velocity profile takes a negative or infinite time or the whole path takes no time at all
+    InvalidVelocityProfile {
+        leg_time_offset: f64,
+        leg_time_slope: f64,
+    },
+    /// A timed path would need more than `MAX_TIMED_SAMPLES` samples
+    TooManySamples(f64),
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::EmptyWord => write!(f, "the word is empty"),
+            PathError::MissingKey { character, index } => write!(
+                f,
+                "the layout has no key for the character '{}' at index {}",
+                character, index
+            ),
+            PathError::InvalidPointDensity(density) => write!(
+                f,
+                "the point density must be a positive number, but it is {}",
+                density
+            ),
+            PathError::InvalidSampleInterval(interval) => write!(
+                f,
+                "the sample interval must be a positive number, but it is {}",
+                interval
+            ),
+            PathError::InvalidPointCount(no_points) => write!(
+                f,
+                "a path with both ends can't be resampled to {} points",
+                no_points
+            ),
+            PathError::EmptyUserPath => write!(f, "the path to compare with the word is empty"),
+            PathError::InvalidVelocityProfile {
+                leg_time_offset,
+                leg_time_slope,
+            } => write!(
+                f,
+                "the velocity profile with a leg time offset of {} and a leg time slope of {} is invalid",
+                leg_time_offset, leg_time_slope
+            ),
+            PathError::TooManySamples(no_samples) => write!(
+                f,
+                "the timed path would need {} samples, but at most {} are allowed",
+                no_samples,
+                crate::MAX_TIMED_SAMPLES
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// Reasons why a layout is invalid
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The layout does not contain any keys
+    NoKeys,
+    /// A coordinate of the key is not a finite number
+    NonFiniteGeometry {
+        /// Label of the key
+        label: String,
+    },
+    /// The shape of the key has a negative size, a corner radius that does not fit or no corners
+    InvalidShape {
+        /// Label of the key
+        label: String,
+    },
+    /// A key produces the character more than once
+    DuplicateCharacter { character: char },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::NoKeys => write!(f, "the layout does not contain any keys"),
+            LayoutError::NonFiniteGeometry { label } => write!(
+                f,
+                "the key '{}' has a coordinate that is not a finite number",
+                label
+            ),
+            LayoutError::InvalidShape { label } => {
+                write!(f, "the key '{}' has an invalid shape", label)
+            }
+            LayoutError::DuplicateCharacter { character } => write!(
+                f,
+                "the character '{}' is produced more than once by the same key",
+                character
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+/// Deepest nesting of values or elements the parsers of layout files accept
+/// Files are parsed recursively, so deeper nesting could overflow the stack
+pub(crate) const MAX_NESTING_DEPTH: usize = 128;
+
+/// Reasons why a layout file can't be imported
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportError {
+    /// The file is not well-formed
+    Syntax {
+        /// Line of the file, starting at 1
+        line: usize,
+        /// Description of the problem
+        message: String,
+    },
+    /// A required field is missing
+    MissingField {
+        /// Path of the field in the file
+        field: String,
+    },
+    /// A field has a value that can't be used
+    InvalidValue {
+        /// Path of the field in the file
+        field: String,
+        /// The value of the field
+        value: String,
+    },
+    /// The imported keys don't form a valid layout
+    Layout(LayoutError),
+}
+
+impl ImportError {
+    /// Create the error for a required field that is missing
+    pub(crate) fn missing_field(field: &str) -> Self {
+        ImportError::MissingField {
+            field: field.to_string(),
+        }
+    }
+
+    /// Create the error for a field with a value that can't be used
+    pub(crate) fn invalid_value(field: &str, value: &str) -> Self {
+        ImportError::InvalidValue {
+            field: field.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Syntax { line, message } => write!(f, "line {}: {}", line, message),
+            ImportError::MissingField { field } => write!(f, "the field '{}' is missing", field),
+            ImportError::InvalidValue { field, value } => {
+                write!(f, "the field '{}' has the invalid value '{}'", field, value)
+            }
+            ImportError::Layout(error) => write!(f, "the layout is invalid: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ImportError::Layout(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<LayoutError> for ImportError {
+    fn from(error: LayoutError) -> Self {
+        ImportError::Layout(error)
+    }
+}
+
+/// Reasons why a transform between coordinate spaces can't be created
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransformError {
+    /// The area inside of the padding of the widget is not a positive size
+    InvalidWidgetSize {
+        /// Width of the area inside of the padding
+        width: f64,
+        /// Height of the area inside of the padding
+        height: f64,
+    },
+    /// The resolution of the screen is not a positive number
+    InvalidDpi(f64),
+    /// The bounds of the layout have no area, so they can't be scaled to the widget
+    EmptyBounds,
+}
+
+impl fmt::Display for TransformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformError::InvalidWidgetSize { width, height } => write!(
+                f,
+                "the widget must have a positive size inside of its padding, but it is {}x{}",
+                width, height
+            ),
+            TransformError::InvalidDpi(dpi) => write!(
+                f,
+                "the resolution must be a positive number, but it is {}",
+                dpi
+            ),
+            TransformError::EmptyBounds => write!(f, "the bounds of the layout have no area"),
+        }
+    }
+}
+
+impl std::error::Error for TransformError {}
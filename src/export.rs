@@ -0,0 +1,31 @@
+use crate::KeyLayout;
+use std::fmt::Write;
+
+impl KeyLayout {
+    /// Serialize the centers of the keys into the source code of a Rust function
+    /// The function returns the centers in the same format as `get_default_buttons_centers()`
+    pub fn to_rust_source(&self, function_name: &str) -> String {
+        let mut centers: Vec<(String, (f64, f64))> = self.to_centers().into_iter().collect();
+        centers.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut source = String::new();
+        writeln!(
+            source,
+            "pub fn {}() -> HashMap<String, (f64, f64)> {{",
+            function_name
+        )
+        .unwrap();
+        writeln!(source, "    let mut buttons = HashMap::new();").unwrap();
+        for (label, (x, y)) in centers {
+            writeln!(
+                source,
+                "    buttons.insert({:?}.to_string(), ({:?}, {:?}));",
+                label, x, y
+            )
+            .unwrap();
+        }
+        writeln!(source, "    buttons").unwrap();
+        writeln!(source, "}}").unwrap();
+        source
+    }
+}
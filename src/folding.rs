@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+
+// Lowercase letters with diacritics and the letters they are folded to
+const DIACRITIC_FOLDING: &[(char, &str)] = &[
+    // Latin-1 Supplement
+    ('à', "a"),
+    ('á', "a"),
+    ('â', "a"),
+    ('ã', "a"),
+    ('ä', "a"),
+    ('å', "a"),
+    ('æ', "ae"),
+    ('ç', "c"),
+    ('è', "e"),
+    ('é', "e"),
+    ('ê', "e"),
+    ('ë', "e"),
+    ('ì', "i"),
+    ('í', "i"),
+    ('î', "i"),
+    ('ï', "i"),
+    ('ð', "d"),
+    ('ñ', "n"),
+    ('ò', "o"),
+    ('ó', "o"),
+    ('ô', "o"),
+    ('õ', "o"),
+    ('ö', "o"),
+    ('ø', "o"),
+    ('ù', "u"),
+    ('ú', "u"),
+    ('û', "u"),
+    ('ü', "u"),
+    ('ý', "y"),
+    ('þ', "th"),
+    ('ÿ', "y"),
+    ('ß', "ss"),
+    // Latin Extended-A
+    ('ā', "a"),
+    ('ă', "a"),
+    ('ą', "a"),
+    ('ć', "c"),
+    ('ĉ', "c"),
+    ('ċ', "c"),
+    ('č', "c"),
+    ('ď', "d"),
+    ('đ', "d"),
+    ('ē', "e"),
+    ('ĕ', "e"),
+    ('ė', "e"),
+    ('ę', "e"),
+    ('ě', "e"),
+    ('ĝ', "g"),
+    ('ğ', "g"),
+    ('ġ', "g"),
+    ('ģ', "g"),
+    ('ĥ', "h"),
+    ('ħ', "h"),
+    ('ĩ', "i"),
+    ('ī', "i"),
+    ('ĭ', "i"),
+    ('į', "i"),
+    ('ı', "i"),
+    ('ĳ', "ij"),
+    ('ĵ', "j"),
+    ('ķ', "k"),
+    ('ĺ', "l"),
+    ('ļ', "l"),
+    ('ľ', "l"),
+    ('ŀ', "l"),
+    ('ł', "l"),
+    ('ń', "n"),
+    ('ņ', "n"),
+    ('ň', "n"),
+    ('ō', "o"),
+    ('ŏ', "o"),
+    ('ő', "o"),
+    ('œ', "oe"),
+    ('ŕ', "r"),
+    ('ŗ', "r"),
+    ('ř', "r"),
+    ('ś', "s"),
+    ('ŝ', "s"),
+    ('ş', "s"),
+    ('š', "s"),
+    ('ţ', "t"),
+    ('ť', "t"),
+    ('ŧ', "t"),
+    ('ũ', "u"),
+    ('ū', "u"),
+    ('ŭ', "u"),
+    ('ů', "u"),
+    ('ű', "u"),
+    ('ų', "u"),
+    ('ŵ', "w"),
+    ('ŷ', "y"),
+    ('ź', "z"),
+    ('ż', "z"),
+    ('ž', "z"),
+    // Latin Extended-B
+    ('ș', "s"),
+    ('ț', "t"),
+    // Greek
+    ('ά', "α"),
+    ('έ', "ε"),
+    ('ή', "η"),
+    ('ί', "ι"),
+    ('ΐ', "ι"),
+    ('ϊ', "ι"),
+    ('ό', "ο"),
+    ('ύ', "υ"),
+    ('ΰ', "υ"),
+    ('ϋ', "υ"),
+    ('ώ', "ω"),
+    ('ς', "σ"),
+    // Cyrillic
+    ('ё', "е"),
+];
+
+/// Get the table that folds letters with diacritics to their base letters, e.g. 'é' to "e" and 'ß' to "ss"
+///
+/// The table contains lowercase letters of the Latin, Greek and Cyrillic scripts. Combining diacritical marks
+/// (U+0300 to U+036F) are folded to nothing, so decomposed letters lose their marks as well.
+/// The table can be modified before it is passed to a WordPath to adjust it to a layout.
+pub fn default_diacritic_folding() -> HashMap<char, String> {
+    let mut folding: HashMap<char, String> = DIACRITIC_FOLDING
+        .iter()
+        .map(|&(letter, folded)| (letter, folded.to_string()))
+        .collect();
+    for mark in '\u{300}'..='\u{36f}' {
+        folding.insert(mark, String::new());
+    }
+    folding
+}
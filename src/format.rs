@@ -0,0 +1,503 @@
+use crate::{json, toml};
+use crate::{Bounds, ImportError, Key, KeyLayout, KeyShape, Point, Units};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Version of the layout file format that is written by `KeyLayout::to_json()` and `KeyLayout::to_toml()`
+/// Files with a higher version are rejected. The format is described in `docs/layout-format.md`.
+pub const LAYOUT_FORMAT_VERSION: u32 = 1;
+
+/// A value of a JSON or TOML document
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    /// The entries of a table in the order of the document
+    Table(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// Get the value of a key if the value is a table
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Table(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    // Short description of the value for error messages
+    fn text(&self) -> String {
+        match self {
+            Value::Null => "null".to_string(),
+            Value::Bool(value) => value.to_string(),
+            Value::Number(value) => value.to_string(),
+            Value::String(value) => value.clone(),
+            Value::Array(_) => "an array".to_string(),
+            Value::Table(_) => "a table".to_string(),
+        }
+    }
+}
+
+impl KeyLayout {
+    /// Load a layout from a JSON file in the format described in `docs/layout-format.md`
+    pub fn from_json(source: &str) -> Result<Self, ImportError> {
+        layout_from_value(&json::parse(source)?)
+    }
+
+    /// Load a layout from a TOML file in the format described in `docs/layout-format.md`
+    pub fn from_toml(source: &str) -> Result<Self, ImportError> {
+        layout_from_value(&toml::parse(source)?)
+    }
+
+    /// Serialize the layout into JSON in the format described in `docs/layout-format.md`
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        writeln!(json, "{{").unwrap();
+        writeln!(json, "  \"version\": {},", LAYOUT_FORMAT_VERSION).unwrap();
+        writeln!(json, "  \"name\": {},", quote(self.name())).unwrap();
+        writeln!(json, "  \"units\": {},", quote(self.units().name())).unwrap();
+        writeln!(json, "  \"bounds\": {},", json_bounds(&self.bounds())).unwrap();
+        if let Some(folding) = self.diacritic_folding() {
+            writeln!(
+                json,
+                "  \"diacritic_folding\": {},",
+                folding_table(folding, ":")
+            )
+            .unwrap();
+        }
+        writeln!(json, "  \"keys\": [").unwrap();
+        for (idx, key) in self.keys().iter().enumerate() {
+            let separator = if idx + 1 < self.keys().len() { "," } else { "" };
+            writeln!(json, "    {}{}", json_key(key), separator).unwrap();
+        }
+        writeln!(json, "  ]").unwrap();
+        writeln!(json, "}}").unwrap();
+        json
+    }
+
+    /// Serialize the layout into TOML in the format described in `docs/layout-format.md`
+    pub fn to_toml(&self) -> String {
+        let mut toml = String::new();
+        writeln!(toml, "version = {}", LAYOUT_FORMAT_VERSION).unwrap();
+        writeln!(toml, "name = {}", quote(self.name())).unwrap();
+        writeln!(toml, "units = {}", quote(self.units().name())).unwrap();
+        let bounds = self.bounds();
+        writeln!(
+            toml,
+            "bounds = {{ min_x = {}, min_y = {}, max_x = {}, max_y = {} }}",
+            number(bounds.min_x),
+            number(bounds.min_y),
+            number(bounds.max_x),
+            number(bounds.max_y)
+        )
+        .unwrap();
+        if let Some(folding) = self.diacritic_folding() {
+            writeln!(toml, "diacritic_folding = {}", folding_table(folding, " =")).unwrap();
+        }
+        for key in self.keys() {
+            writeln!(toml).unwrap();
+            writeln!(toml, "[[keys]]").unwrap();
+            writeln!(toml, "label = {}", quote(&key.label)).unwrap();
+            writeln!(toml, "chars = {}", chars(&key.chars)).unwrap();
+            writeln!(toml, "center = {}", point_text(key.center)).unwrap();
+            match centered_rect_size(key) {
+                Some(size) => writeln!(toml, "size = {}", point_text(size)).unwrap(),
+                None => {
+                    if let Some(shape) = &key.shape {
+                        writeln!(toml, "shape = {}", toml_shape(shape)).unwrap();
+                    }
+                }
+            }
+        }
+        toml
+    }
+}
+
+fn layout_from_value(document: &Value) -> Result<KeyLayout, ImportError> {
+    let version = match document.get("version") {
+        Some(Value::Number(version)) => *version,
+        Some(value) => return Err(ImportError::invalid_value("version", &value.text())),
+        None => return Err(ImportError::missing_field("version")),
+    };
+    if version.fract() != 0.0 || version < 1.0 || version > LAYOUT_FORMAT_VERSION as f64 {
+        return Err(ImportError::invalid_value("version", &version.to_string()));
+    }
+    let name =
+        string(document, "name", "name")?.ok_or_else(|| ImportError::missing_field("name"))?;
+    let units = match document.get("units") {
+        Some(Value::String(name)) => {
+            Units::from_name(name).ok_or_else(|| ImportError::invalid_value("units", name))?
+        }
+        Some(value) => return Err(ImportError::invalid_value("units", &value.text())),
+        None => Units::default(),
+    };
+
+    let keys = match document.get("keys") {
+        Some(Value::Array(keys)) => keys,
+        Some(value) => return Err(ImportError::invalid_value("keys", &value.text())),
+        None => return Err(ImportError::missing_field("keys")),
+    };
+    let keys = keys
+        .iter()
+        .enumerate()
+        .map(|(idx, key)| key_from_value(key, &format!("keys[{}]", idx)))
+        .collect::<Result<Vec<Key>, ImportError>>()?;
+    let mut key_layout = KeyLayout::new(name, keys)?.with_units(units);
+    if let Some(folding) = document.get("diacritic_folding") {
+        key_layout = key_layout.with_diacritic_folding(folding_from_value(folding)?);
+    }
+
+    match document.get("bounds") {
+        Some(value) => {
+            let coordinate = |name: &str| -> Result<f64, ImportError> {
+                number_field(value, name, &format!("bounds.{}", name))?
+                    .ok_or_else(|| ImportError::missing_field(&format!("bounds.{}", name)))
+            };
+            let bounds = Bounds {
+                min_x: coordinate("min_x")?,
+                min_y: coordinate("min_y")?,
+                max_x: coordinate("max_x")?,
+                max_y: coordinate("max_y")?,
+            };
+            if bounds.min_x > bounds.max_x || bounds.min_y > bounds.max_y {
+                return Err(ImportError::invalid_value("bounds", &value.text()));
+            }
+            Ok(key_layout.with_bounds(bounds))
+        }
+        None => Ok(key_layout),
+    }
+}
+
+fn key_from_value(value: &Value, field: &str) -> Result<Key, ImportError> {
+    if !matches!(value, Value::Table(_)) {
+        return Err(ImportError::invalid_value(field, &value.text()));
+    }
+    let label = string(value, "label", &format!("{}.label", field))?
+        .ok_or_else(|| ImportError::missing_field(&format!("{}.label", field)))?;
+    let center = point_field(value, "center", &format!("{}.center", field))?;
+    let size = point_field(value, "size", &format!("{}.size", field))?;
+    let shape = match value.get("shape") {
+        Some(shape) => Some(shape_from_value(shape, &format!("{}.shape", field))?),
+        None => None,
+    };
+
+    let mut key = match (center, size, shape) {
+        (_, Some(_), Some(_)) => {
+            return Err(ImportError::invalid_value(
+                &format!("{}.size", field),
+                "a size and a shape",
+            ))
+        }
+        (Some(center), Some((width, height)), None) => {
+            Key::new(label, center).with_shape(KeyShape::Rect {
+                x: center.0 - width / 2.0,
+                y: center.1 - height / 2.0,
+                width,
+                height,
+            })
+        }
+        (Some(center), None, Some(shape)) => Key::new(label, center).with_shape(shape),
+        (None, _, Some(shape)) => Key::from_shape(label, shape),
+        (Some(center), None, None) => Key::new(label, center),
+        (None, _, None) => return Err(ImportError::missing_field(&format!("{}.center", field))),
+    };
+
+    match value.get("chars") {
+        Some(Value::Array(chars)) => {
+            key.chars = chars
+                .iter()
+                .enumerate()
+                .map(|(idx, value)| {
+                    let field = format!("{}.chars[{}]", field, idx);
+                    let mut characters = match value {
+                        Value::String(characters) => characters.chars(),
+                        _ => return Err(ImportError::invalid_value(&field, &value.text())),
+                    };
+                    match (characters.next(), characters.next()) {
+                        (Some(character), None) => Ok(character),
+                        _ => Err(ImportError::invalid_value(&field, &value.text())),
+                    }
+                })
+                .collect::<Result<Vec<char>, ImportError>>()?
+        }
+        Some(value) => {
+            return Err(ImportError::invalid_value(
+                &format!("{}.chars", field),
+                &value.text(),
+            ))
+        }
+        None => {}
+    }
+    Ok(key)
+}
+
+// The folding table maps strings of one character to the strings they are folded to
+fn folding_from_value(value: &Value) -> Result<HashMap<char, String>, ImportError> {
+    let entries = match value {
+        Value::Table(entries) => entries,
+        _ => {
+            return Err(ImportError::invalid_value(
+                "diacritic_folding",
+                &value.text(),
+            ))
+        }
+    };
+    entries
+        .iter()
+        .map(|(character, folded)| {
+            let field = format!("diacritic_folding.{}", character);
+            let mut characters = character.chars();
+            let character = match (characters.next(), characters.next()) {
+                (Some(character), None) => character,
+                _ => return Err(ImportError::invalid_value("diacritic_folding", character)),
+            };
+            match folded {
+                Value::String(folded) => Ok((character, folded.clone())),
+                _ => Err(ImportError::invalid_value(&field, &folded.text())),
+            }
+        })
+        .collect()
+}
+
+fn shape_from_value(value: &Value, field: &str) -> Result<KeyShape, ImportError> {
+    let dimension = |name: &str| -> Result<f64, ImportError> {
+        number_field(value, name, &format!("{}.{}", field, name))?
+            .ok_or_else(|| ImportError::missing_field(&format!("{}.{}", field, name)))
+    };
+    let shape_type = string(value, "type", &format!("{}.type", field))?
+        .ok_or_else(|| ImportError::missing_field(&format!("{}.type", field)))?;
+    match shape_type {
+        "rect" => Ok(KeyShape::Rect {
+            x: dimension("x")?,
+            y: dimension("y")?,
+            width: dimension("width")?,
+            height: dimension("height")?,
+        }),
+        "rounded_rect" => Ok(KeyShape::RoundedRect {
+            x: dimension("x")?,
+            y: dimension("y")?,
+            width: dimension("width")?,
+            height: dimension("height")?,
+            radius: dimension("radius")?,
+        }),
+        "polygon" => {
+            let corners_field = format!("{}.corners", field);
+            match value.get("corners") {
+                Some(Value::Array(corners)) => corners
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, corner)| point(corner, &format!("{}[{}]", corners_field, idx)))
+                    .collect::<Result<Vec<Point>, ImportError>>()
+                    .map(KeyShape::Polygon),
+                Some(corners) => Err(ImportError::invalid_value(&corners_field, &corners.text())),
+                None => Err(ImportError::missing_field(&corners_field)),
+            }
+        }
+        _ => Err(ImportError::invalid_value(
+            &format!("{}.type", field),
+            shape_type,
+        )),
+    }
+}
+
+// Get an optional string field of a table
+fn string<'v>(table: &'v Value, name: &str, field: &str) -> Result<Option<&'v str>, ImportError> {
+    match table.get(name) {
+        Some(Value::String(value)) => Ok(Some(value)),
+        Some(value) => Err(ImportError::invalid_value(field, &value.text())),
+        None => Ok(None),
+    }
+}
+
+// Get an optional number field of a table, which must be finite
+fn number_field(table: &Value, name: &str, field: &str) -> Result<Option<f64>, ImportError> {
+    match table.get(name) {
+        Some(Value::Number(value)) if value.is_finite() => Ok(Some(*value)),
+        Some(value) => Err(ImportError::invalid_value(field, &value.text())),
+        None => Ok(None),
+    }
+}
+
+// Get an optional point field of a table
+fn point_field(table: &Value, name: &str, field: &str) -> Result<Option<Point>, ImportError> {
+    table.get(name).map(|value| point(value, field)).transpose()
+}
+
+// A point is an array of two finite numbers
+fn point(value: &Value, field: &str) -> Result<Point, ImportError> {
+    match value {
+        Value::Array(coordinates) => match coordinates.as_slice() {
+            [Value::Number(x), Value::Number(y)] if x.is_finite() && y.is_finite() => Ok((*x, *y)),
+            _ => Err(ImportError::invalid_value(field, &value.text())),
+        },
+        _ => Err(ImportError::invalid_value(field, &value.text())),
+    }
+}
+
+// A key whose shape is a rectangle around its center is written with its size instead of its shape
+// The corner is calculated from the center when reading, so it has to result in exactly the same corner
+fn centered_rect_size(key: &Key) -> Option<Point> {
+    match key.shape {
+        Some(KeyShape::Rect {
+            x,
+            y,
+            width,
+            height,
+        }) if (key.center.0 - width / 2.0, key.center.1 - height / 2.0) == (x, y) => {
+            Some((width, height))
+        }
+        _ => None,
+    }
+}
+
+fn json_bounds(bounds: &Bounds) -> String {
+    format!(
+        "{{ \"min_x\": {}, \"min_y\": {}, \"max_x\": {}, \"max_y\": {} }}",
+        number(bounds.min_x),
+        number(bounds.min_y),
+        number(bounds.max_x),
+        number(bounds.max_y)
+    )
+}
+
+fn json_key(key: &Key) -> String {
+    let mut json = format!(
+        "{{ \"label\": {}, \"chars\": {}, \"center\": {}",
+        quote(&key.label),
+        chars(&key.chars),
+        point_text(key.center)
+    );
+    match centered_rect_size(key) {
+        Some(size) => write!(json, ", \"size\": {}", point_text(size)).unwrap(),
+        None => {
+            if let Some(shape) = &key.shape {
+                write!(json, ", \"shape\": {}", json_shape(shape)).unwrap();
+            }
+        }
+    }
+    json.push_str(" }");
+    json
+}
+
+fn json_shape(shape: &KeyShape) -> String {
+    let fields = shape_fields(shape)
+        .iter()
+        .map(|(field, value)| format!("\"{}\": {}", field, value))
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("{{ {} }}", fields)
+}
+
+fn toml_shape(shape: &KeyShape) -> String {
+    let fields = shape_fields(shape)
+        .iter()
+        .map(|(field, value)| format!("{} = {}", field, value))
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("{{ {} }}", fields)
+}
+
+// Fields of a shape with their serialized values, which are the same in JSON and TOML
+fn shape_fields(shape: &KeyShape) -> Vec<(&'static str, String)> {
+    match shape {
+        KeyShape::Rect {
+            x,
+            y,
+            width,
+            height,
+        } => vec![
+            ("type", quote("rect")),
+            ("x", number(*x)),
+            ("y", number(*y)),
+            ("width", number(*width)),
+            ("height", number(*height)),
+        ],
+        KeyShape::RoundedRect {
+            x,
+            y,
+            width,
+            height,
+            radius,
+        } => vec![
+            ("type", quote("rounded_rect")),
+            ("x", number(*x)),
+            ("y", number(*y)),
+            ("width", number(*width)),
+            ("height", number(*height)),
+            ("radius", number(*radius)),
+        ],
+        KeyShape::Polygon(corners) => {
+            let corners: Vec<String> = corners.iter().map(|&corner| point_text(corner)).collect();
+            vec![
+                ("type", quote("polygon")),
+                ("corners", format!("[{}]", corners.join(", "))),
+            ]
+        }
+    }
+}
+
+// Numbers are written in the shortest form that is parsed to the same f64. It always contains a decimal point or an
+// exponent, so TOML reads it as a float
+fn number(value: f64) -> String {
+    format!("{:?}", value)
+}
+
+// Inline table of the folding table sorted by the characters, the separator differs between JSON and TOML
+fn folding_table(folding: &HashMap<char, String>, separator: &str) -> String {
+    let mut entries: Vec<(&char, &String)> = folding.iter().collect();
+    entries.sort();
+    let entries: Vec<String> = entries
+        .into_iter()
+        .map(|(character, folded)| {
+            format!(
+                "{}{} {}",
+                quote(&character.to_string()),
+                separator,
+                quote(folded)
+            )
+        })
+        .collect();
+    if entries.is_empty() {
+        return "{}".to_string();
+    }
+    format!("{{ {} }}", entries.join(", "))
+}
+
+fn point_text((x, y): Point) -> String {
+    format!("[{}, {}]", number(x), number(y))
+}
+
+fn chars(chars: &[char]) -> String {
+    let chars: Vec<String> = chars
+        .iter()
+        .map(|character| quote(&character.to_string()))
+        .collect();
+    format!("[{}]", chars.join(", "))
+}
+
+// Quote and escape a string. The escape sequences are the same for JSON and basic strings of TOML
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            character if character.is_control() => {
+                write!(quoted, "\\u{:04x}", character as u32).unwrap()
+            }
+            character => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,97 @@
+use crate::{dist, Key, KeyLayout, KeyShape, Point};
+
+impl KeyShape {
+    /// Check if the point is inside the shape or on its border
+    pub fn contains(&self, point: &Point) -> bool {
+        match self {
+            KeyShape::Rect { .. } => self.bounds().contains(point),
+            KeyShape::RoundedRect {
+                x,
+                y,
+                width,
+                height,
+                radius,
+            } => {
+                if !self.bounds().contains(point) {
+                    return false;
+                }
+                // Outside of the corners the point has to be within the radius of the center of the corner circle
+                let corner_x = point.0.max(x + radius).min(x + width - radius);
+                let corner_y = point.1.max(y + radius).min(y + height - radius);
+                dist(point, &(corner_x, corner_y)) <= *radius
+            }
+            KeyShape::Polygon(corners) => polygon_contains(corners, point),
+        }
+    }
+}
+
+// Check if the point is inside the polygon with the even-odd rule or on one of its edges
+fn polygon_contains(corners: &[Point], point: &Point) -> bool {
+    let mut is_inside = false;
+    for (idx, start) in corners.iter().enumerate() {
+        let end = &corners[(idx + 1) % corners.len()];
+        if is_on_edge(start, end, point) {
+            return true;
+        }
+        // Count the edges that cross a ray from the point to the right
+        if (start.1 > point.1) != (end.1 > point.1) {
+            let crossing_x = start.0 + (point.1 - start.1) / (end.1 - start.1) * (end.0 - start.0);
+            if point.0 < crossing_x {
+                is_inside = !is_inside;
+            }
+        }
+    }
+    is_inside
+}
+
+fn is_on_edge(start: &Point, end: &Point, point: &Point) -> bool {
+    let cross = (end.0 - start.0) * (point.1 - start.1) - (end.1 - start.1) * (point.0 - start.0);
+    let length = dist(start, end);
+    if cross.abs() > 1e-12 * length.max(1.0) {
+        return false;
+    }
+    point.0 >= start.0.min(end.0)
+        && point.0 <= start.0.max(end.0)
+        && point.1 >= start.1.min(end.1)
+        && point.1 <= start.1.max(end.1)
+}
+
+/// Hit testing against the geometry of the keys
+/// Lookups without key shapes, like the `HashMap` of `get_default_buttons_centers()`, can use
+/// `KeyLookup::nearest_positions()` instead.
+impl KeyLayout {
+    /// Get the key whose shape contains the point, e.g. a touch point
+    /// If shapes overlap, the key with the closest center is returned. Keys without a known geometry are ignored, so
+    /// None is returned if no shape contains the point.
+    pub fn key_containing(&self, point: Point) -> Option<&Key> {
+        self.keys()
+            .iter()
+            .filter(|key| {
+                key.shape
+                    .as_ref()
+                    .is_some_and(|shape| shape.contains(&point))
+            })
+            .min_by(|a, b| dist(&a.center, &point).total_cmp(&dist(&b.center, &point)))
+    }
+
+    /// Get the key under the point
+    /// It is the key whose shape contains the point. If there is none, e.g. because the point is in a gap between
+    /// keys or the geometry of the keys is not known, the key with the closest center is returned instead.
+    pub fn key_at(&self, point: Point) -> &Key {
+        self.key_containing(point)
+            .unwrap_or_else(|| self.nearest_keys(point, 1)[0].0)
+    }
+
+    /// Get up to k keys with the closest centers to the point together with their distances
+    /// The keys are sorted by their distance. Keys with the same distance keep their order in the layout.
+    pub fn nearest_keys(&self, point: Point, k: usize) -> Vec<(&Key, f64)> {
+        let mut keys: Vec<(&Key, f64)> = self
+            .keys()
+            .iter()
+            .map(|key| (key, dist(&key.center, &point)))
+            .collect();
+        keys.sort_by(|a, b| a.1.total_cmp(&b.1));
+        keys.truncate(k);
+        keys
+    }
+}
@@ -0,0 +1,242 @@
+use crate::error::MAX_NESTING_DEPTH;
+use crate::format::Value;
+use crate::ImportError;
+
+/// Parse a JSON document
+pub(crate) fn parse(source: &str) -> Result<Value, ImportError> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        pos: 0,
+        depth: 0,
+    };
+    let document = parser.parse_value()?;
+    parser.skip_whitespace();
+    if parser.pos < parser.chars.len() {
+        return Err(parser.error("unexpected characters after the document"));
+    }
+    Ok(document)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    // Number of objects and arrays the parser is in
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> ImportError {
+        ImportError::Syntax {
+            line: self.chars[..self.pos.min(self.chars.len())]
+                .iter()
+                .filter(|&&character| character == '\n')
+                .count()
+                + 1,
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ImportError> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("expected '{}'", expected)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<Value, ImportError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(opening @ ('{' | '[')) => {
+                if self.depth >= MAX_NESTING_DEPTH {
+                    return Err(self.error(&format!(
+                        "objects and arrays are nested more than {} levels deep",
+                        MAX_NESTING_DEPTH
+                    )));
+                }
+                self.depth += 1;
+                let value = match opening {
+                    '{' => self.parse_object(),
+                    _ => self.parse_array(),
+                };
+                self.depth -= 1;
+                value
+            }
+            Some('"') => self.parse_string().map(Value::String),
+            Some('-' | '0'..='9') => self.parse_number(),
+            Some(_) => self.parse_literal(),
+            None => Err(self.error("unexpected end of the document")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Value, ImportError> {
+        self.pos += 1;
+        let mut entries: Vec<(String, Value)> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Value::Table(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("expected a string as key"));
+            }
+            let key = self.parse_string()?;
+            if entries.iter().any(|(entry_key, _)| *entry_key == key) {
+                return Err(self.error(&format!("duplicate key '{}'", key)));
+            }
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            entries.push((key, value));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(Value::Table(entries));
+                }
+                _ => return Err(self.error("expected ',' or '}' in an object")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value, ImportError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(Value::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("expected ',' or ']' in an array")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, ImportError> {
+        self.pos += 1;
+        let mut string = String::new();
+        loop {
+            let character = self
+                .peek()
+                .ok_or_else(|| self.error("unterminated string"))?;
+            self.pos += 1;
+            match character {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escape = self
+                        .peek()
+                        .ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    let escaped = match escape {
+                        '"' | '\\' | '/' => escape,
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => self.parse_unicode_escape()?,
+                        _ => {
+                            return Err(
+                                self.error(&format!("unknown escape sequence '\\{}'", escape))
+                            )
+                        }
+                    };
+                    string.push(escaped);
+                }
+                character if character.is_control() => {
+                    return Err(self.error("control characters must be escaped in strings"))
+                }
+                character => string.push(character),
+            }
+        }
+    }
+
+    // Characters outside of the basic multilingual plane are escaped as a surrogate pair
+    fn parse_unicode_escape(&mut self) -> Result<char, ImportError> {
+        let high = self.parse_hex4()?;
+        if !(0xd800..0xdc00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| self.error("invalid unicode escape"));
+        }
+        if self.peek() != Some('\\') || self.chars.get(self.pos + 1) != Some(&'u') {
+            return Err(self.error("unpaired surrogate in unicode escape"));
+        }
+        self.pos += 2;
+        let low = self.parse_hex4()?;
+        if !(0xdc00..0xe000).contains(&low) {
+            return Err(self.error("unpaired surrogate in unicode escape"));
+        }
+        char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+            .ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn parse_hex4(&mut self) -> Result<u32, ImportError> {
+        let digits: String = self.chars.iter().skip(self.pos).take(4).collect();
+        if digits.len() != 4 {
+            return Err(self.error("incomplete unicode escape"));
+        }
+        let code =
+            u32::from_str_radix(&digits, 16).map_err(|_| self.error("invalid unicode escape"))?;
+        self.pos += 4;
+        Ok(code)
+    }
+
+    fn parse_number(&mut self) -> Result<Value, ImportError> {
+        let start = self.pos;
+        while matches!(self.peek(), Some('-' | '+' | '.' | 'e' | 'E' | '0'..='9')) {
+            self.pos += 1;
+        }
+        let number: String = self.chars[start..self.pos].iter().collect();
+        // Rust accepts numbers that JSON doesn't, so the grammar of JSON is checked first
+        let digits = number.strip_prefix('-').unwrap_or(&number);
+        let has_leading_zero =
+            digits.len() > 1 && digits.starts_with('0') && digits.as_bytes()[1].is_ascii_digit();
+        let is_valid = digits.starts_with(|character: char| character.is_ascii_digit())
+            && !digits.contains(".e")
+            && !digits.contains(".E")
+            && !digits.ends_with('.')
+            && !has_leading_zero;
+        match number.parse::<f64>() {
+            Ok(value) if is_valid => Ok(Value::Number(value)),
+            _ => Err(self.error(&format!("invalid number '{}'", number))),
+        }
+    }
+
+    fn parse_literal(&mut self) -> Result<Value, ImportError> {
+        let start = self.pos;
+        while matches!(self.peek(), Some('a'..='z')) {
+            self.pos += 1;
+        }
+        let literal: String = self.chars[start..self.pos].iter().collect();
+        match literal.as_str() {
+            "true" => Ok(Value::Bool(true)),
+            "false" => Ok(Value::Bool(false)),
+            "null" => Ok(Value::Null),
+            _ => {
+                self.pos = start;
+                Err(self.error("unexpected character"))
+            }
+        }
+    }
+}
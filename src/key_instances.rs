@@ -0,0 +1,58 @@
+use crate::{dist, Point};
+
+/// Choose one position for each letter so that the straight path through them is as short as possible
+///
+/// Each letter has the positions of all keys producing it. The shortest path is found with dynamic programming over
+/// the letters, which only has to compare the positions of neighbouring letters. The index of the chosen position is
+/// returned for each letter. Letters without a position get 0 and split the word into parts that are chosen
+/// independently. If several choices result in the same length, the keys that come first are preferred.
+pub(crate) fn choose_key_instances(candidates: &[Vec<Point>]) -> Vec<usize> {
+    // Length of the shortest path ending in each position of each letter and the position of the previous letter
+    // on that path
+    let mut steps: Vec<Vec<(f64, usize)>> = Vec::with_capacity(candidates.len());
+    for (idx, positions) in candidates.iter().enumerate() {
+        let prev_positions = match idx {
+            0 => &[][..],
+            _ => &candidates[idx - 1][..],
+        };
+        let letter_steps = positions
+            .iter()
+            .map(|position| {
+                let mut best = (0.0, 0);
+                for (prev_idx, prev_position) in prev_positions.iter().enumerate() {
+                    let length = steps[idx - 1][prev_idx].0 + dist(prev_position, position);
+                    if prev_idx == 0 || length < best.0 {
+                        best = (length, prev_idx);
+                    }
+                }
+                best
+            })
+            .collect();
+        steps.push(letter_steps);
+    }
+
+    // Follow the shortest paths back from the last letter of each part of the word
+    let mut instances = vec![0; candidates.len()];
+    let mut next_instance: Option<usize> = None;
+    for idx in (0..candidates.len()).rev() {
+        if candidates[idx].is_empty() {
+            next_instance = None;
+            continue;
+        }
+        let instance = match next_instance {
+            Some(next_instance) => steps[idx + 1][next_instance].1,
+            None => {
+                let mut shortest = 0;
+                for (instance, &(length, _)) in steps[idx].iter().enumerate() {
+                    if length < steps[idx][shortest].0 {
+                        shortest = instance;
+                    }
+                }
+                shortest
+            }
+        };
+        instances[idx] = instance;
+        next_instance = Some(instance);
+    }
+    instances
+}
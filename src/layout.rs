@@ -0,0 +1,598 @@
+use crate::{dist, LayoutError, Point};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Lookup of the keys of a keyboard
+///
+/// WordPath uses it to find the waypoints of a word, so it can be implemented directly on the key model of an
+/// on-screen keyboard. It is implemented for `KeyLayout` and for the `HashMap` returned by
+/// `get_default_buttons_centers()`.
+pub trait KeyLookup {
+    /// Get the position of the key producing the character
+    /// If several keys produce the character, it is the position of the first one
+    fn key_position(&self, character: char) -> Option<Point>;
+
+    /// Get the positions of all keys producing the character, e.g. on split keyboards with a letter on both halves
+    /// The first position is the one returned by `key_position()`. By default a character has at most one key.
+    fn key_positions(&self, character: char) -> Vec<Point> {
+        self.key_position(character).into_iter().collect()
+    }
+
+    /// Get the number of keys producing the character without allocating their positions
+    fn key_count(&self, character: char) -> usize {
+        usize::from(self.key_position(character).is_some())
+    }
+
+    /// Get the labels and the positions of all keys
+    fn all_keys(&self) -> Vec<(String, Point)>;
+
+    /// Get the letters a character without a key is folded to if diacritic folding is enabled in the options
+    /// By default the folding table of the options is used. Layouts can override it, e.g. to fold 'ß' to "ss".
+    fn fold_character(&self, character: char, folding: &HashMap<char, String>) -> Option<String> {
+        folding.get(&character).cloned()
+    }
+
+    /// Get up to k keys with the closest positions to the point, e.g. a touch point, with their distances
+    /// The keys are sorted by their distance and then by their label. Only the positions of the keys are used, so it
+    /// works for any lookup. Use `KeyLayout::key_at()` to hit test against the shapes of the keys.
+    fn nearest_positions(&self, point: Point, k: usize) -> Vec<(String, Point, f64)> {
+        let mut keys: Vec<(String, Point, f64)> = self
+            .all_keys()
+            .into_iter()
+            .map(|(label, position)| {
+                let distance = dist(&position, &point);
+                (label, position, distance)
+            })
+            .collect();
+        keys.sort_by(|a, b| a.2.total_cmp(&b.2).then_with(|| a.0.cmp(&b.0)));
+        keys.truncate(k);
+        keys
+    }
+}
+
+impl<T: KeyLookup + ?Sized> KeyLookup for &T {
+    fn key_position(&self, character: char) -> Option<Point> {
+        (**self).key_position(character)
+    }
+
+    fn key_positions(&self, character: char) -> Vec<Point> {
+        (**self).key_positions(character)
+    }
+
+    fn key_count(&self, character: char) -> usize {
+        (**self).key_count(character)
+    }
+
+    fn all_keys(&self) -> Vec<(String, Point)> {
+        (**self).all_keys()
+    }
+
+    fn fold_character(&self, character: char, folding: &HashMap<char, String>) -> Option<String> {
+        (**self).fold_character(character, folding)
+    }
+}
+
+impl<T: KeyLookup + ?Sized> KeyLookup for Arc<T> {
+    fn key_position(&self, character: char) -> Option<Point> {
+        (**self).key_position(character)
+    }
+
+    fn key_positions(&self, character: char) -> Vec<Point> {
+        (**self).key_positions(character)
+    }
+
+    fn key_count(&self, character: char) -> usize {
+        (**self).key_count(character)
+    }
+
+    fn all_keys(&self) -> Vec<(String, Point)> {
+        (**self).all_keys()
+    }
+
+    fn fold_character(&self, character: char, folding: &HashMap<char, String>) -> Option<String> {
+        (**self).fold_character(character, folding)
+    }
+}
+
+impl KeyLookup for HashMap<String, Point> {
+    fn key_position(&self, character: char) -> Option<Point> {
+        // Avoid allocating a String for every lookup
+        let mut buffer = [0; 4];
+        self.get(&*character.encode_utf8(&mut buffer)).copied()
+    }
+
+    fn all_keys(&self) -> Vec<(String, Point)> {
+        self.iter()
+            .map(|(label, &position)| (label.clone(), position))
+            .collect()
+    }
+}
+
+impl KeyLookup for KeyLayout {
+    fn key_position(&self, character: char) -> Option<Point> {
+        self.key_for_char(character).map(|key| key.center)
+    }
+
+    fn key_positions(&self, character: char) -> Vec<Point> {
+        self.keys_for_char(character)
+            .map(|key| key.center)
+            .collect()
+    }
+
+    fn key_count(&self, character: char) -> usize {
+        self.char_to_keys.get(&character).map_or(0, Vec::len)
+    }
+
+    fn all_keys(&self) -> Vec<(String, Point)> {
+        self.keys
+            .iter()
+            .map(|key| (key.label.clone(), key.center))
+            .collect()
+    }
+
+    fn fold_character(&self, character: char, folding: &HashMap<char, String>) -> Option<String> {
+        self.diacritic_folding
+            .as_ref()
+            .unwrap_or(folding)
+            .get(&character)
+            .cloned()
+    }
+}
+
+/// Axis aligned bounding box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl Bounds {
+    /// Create the smallest bounding box containing all points
+    /// None is returned if there are no points
+    pub fn from_points<'p>(points: impl IntoIterator<Item = &'p Point>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let &(x, y) = points.next()?;
+        let mut bounds = Self {
+            min_x: x,
+            min_y: y,
+            max_x: x,
+            max_y: y,
+        };
+        for &(x, y) in points {
+            bounds.min_x = bounds.min_x.min(x);
+            bounds.min_y = bounds.min_y.min(y);
+            bounds.max_x = bounds.max_x.max(x);
+            bounds.max_y = bounds.max_y.max(y);
+        }
+        Some(bounds)
+    }
+
+    /// Create the smallest bounding box containing both bounding boxes
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min_x: self.min_x.min(other.min_x),
+            min_y: self.min_y.min(other.min_y),
+            max_x: self.max_x.max(other.max_x),
+            max_y: self.max_y.max(other.max_y),
+        }
+    }
+
+    pub fn width(&self) -> f64 {
+        self.max_x - self.min_x
+    }
+
+    pub fn height(&self) -> f64 {
+        self.max_y - self.min_y
+    }
+
+    /// Check if the point is inside the bounding box or on its border
+    pub fn contains(&self, point: &Point) -> bool {
+        point.0 >= self.min_x
+            && point.0 <= self.max_x
+            && point.1 >= self.min_y
+            && point.1 <= self.max_y
+    }
+}
+
+/// Geometry of a key
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyShape {
+    /// Axis aligned rectangle given by its top left corner and its size
+    Rect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    /// Axis aligned rectangle with rounded corners given by its top left corner, its size and the corner radius
+    RoundedRect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        radius: f64,
+    },
+    /// Polygon given by its corners in order, e.g. for L-shaped or trapezoidal keys
+    Polygon(Vec<Point>),
+}
+
+impl KeyShape {
+    /// Get the bounding box of the shape
+    pub fn bounds(&self) -> Bounds {
+        match self {
+            KeyShape::Rect {
+                x,
+                y,
+                width,
+                height,
+            }
+            | KeyShape::RoundedRect {
+                x,
+                y,
+                width,
+                height,
+                ..
+            } => Bounds {
+                min_x: *x,
+                min_y: *y,
+                max_x: x + width,
+                max_y: y + height,
+            },
+            KeyShape::Polygon(corners) => Bounds::from_points(corners).unwrap_or(Bounds {
+                min_x: 0.0,
+                min_y: 0.0,
+                max_x: 0.0,
+                max_y: 0.0,
+            }),
+        }
+    }
+
+    /// Get the area of the shape
+    pub fn area(&self) -> f64 {
+        match self {
+            KeyShape::Rect { width, height, .. } => width * height,
+            KeyShape::RoundedRect {
+                width,
+                height,
+                radius,
+                ..
+            } => width * height - (4.0 - std::f64::consts::PI) * radius * radius,
+            KeyShape::Polygon(corners) => signed_area(corners).abs(),
+        }
+    }
+
+    /// Get the centroid of the shape, which is the center of its area
+    /// Polygons without an area, e.g. with all corners on a line, use the average of their corners instead
+    pub fn centroid(&self) -> Point {
+        match self {
+            KeyShape::Rect {
+                x,
+                y,
+                width,
+                height,
+            }
+            | KeyShape::RoundedRect {
+                x,
+                y,
+                width,
+                height,
+                ..
+            } => (x + width / 2.0, y + height / 2.0),
+            KeyShape::Polygon(corners) => {
+                let area = signed_area(corners);
+                if area.abs() < 1e-12 {
+                    let no_corners = corners.len().max(1) as f64;
+                    return (
+                        corners.iter().map(|corner| corner.0).sum::<f64>() / no_corners,
+                        corners.iter().map(|corner| corner.1).sum::<f64>() / no_corners,
+                    );
+                }
+                // Sum up the centroids of the triangles between the origin and each edge weighted by their area
+                let (mut x, mut y) = (0.0, 0.0);
+                for (start, end) in polygon_edges(corners) {
+                    let cross = start.0 * end.1 - end.0 * start.1;
+                    x += (start.0 + end.0) * cross;
+                    y += (start.1 + end.1) * cross;
+                }
+                (x / (6.0 * area), y / (6.0 * area))
+            }
+        }
+    }
+
+    // Check if all coordinates are finite numbers
+    fn is_finite(&self) -> bool {
+        match self {
+            KeyShape::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => [x, y, width, height].iter().all(|value| value.is_finite()),
+            KeyShape::RoundedRect {
+                x,
+                y,
+                width,
+                height,
+                radius,
+            } => [x, y, width, height, radius]
+                .iter()
+                .all(|value| value.is_finite()),
+            KeyShape::Polygon(corners) => corners
+                .iter()
+                .all(|corner| corner.0.is_finite() && corner.1.is_finite()),
+        }
+    }
+
+    // Check if the shape describes an area that can be pressed
+    fn is_valid(&self) -> bool {
+        match self {
+            KeyShape::Rect { width, height, .. } => *width >= 0.0 && *height >= 0.0,
+            KeyShape::RoundedRect {
+                width,
+                height,
+                radius,
+                ..
+            } => {
+                *width >= 0.0
+                    && *height >= 0.0
+                    && *radius >= 0.0
+                    && 2.0 * radius <= width.min(*height)
+            }
+            KeyShape::Polygon(corners) => !corners.is_empty(),
+        }
+    }
+}
+
+// Iterate over the edges of a polygon including the one that closes it
+fn polygon_edges(corners: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    corners
+        .iter()
+        .zip(corners.iter().cycle().skip(1))
+        .map(|(&start, &end)| (start, end))
+}
+
+// Area of a polygon using the shoelace formula. It is negative if the corners are in clockwise order
+fn signed_area(corners: &[Point]) -> f64 {
+    polygon_edges(corners)
+        .map(|(start, end)| start.0 * end.1 - end.0 * start.1)
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Unit of the coordinates of a layout
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Units {
+    /// Coordinates relative to the size of the layout, e.g. from 0.0 to 1.0 like in `get_default_layout()`
+    #[default]
+    Normalized,
+    /// Pixels of a widget
+    Pixels,
+    /// Millimetres on the screen
+    Millimetres,
+}
+
+impl Units {
+    /// Get the name of the unit as used in layout files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Units::Normalized => "normalized",
+            Units::Pixels => "pixels",
+            Units::Millimetres => "millimetres",
+        }
+    }
+
+    /// Get the unit from its name as used in layout files
+    pub fn from_name(name: &str) -> Option<Self> {
+        [Units::Normalized, Units::Pixels, Units::Millimetres]
+            .into_iter()
+            .find(|units| units.name() == name)
+    }
+}
+
+/// A key of a layout
+#[derive(Debug, Clone, PartialEq)]
+pub struct Key {
+    /// Label shown on the key
+    pub label: String,
+    /// Characters that are produced by the key
+    pub chars: Vec<char>,
+    /// Point a swipe passes through to produce the characters of the key
+    pub center: Point,
+    /// Geometry of the key if it is known
+    pub shape: Option<KeyShape>,
+}
+
+impl Key {
+    /// Create a new key without a known geometry
+    /// If the label is a single character, the key produces it
+    pub fn new(label: &str, center: Point) -> Self {
+        let mut chars: Vec<char> = label.chars().collect();
+        if chars.len() != 1 {
+            chars.clear();
+        }
+        Self {
+            label: label.to_string(),
+            chars,
+            center,
+            shape: None,
+        }
+    }
+
+    /// Create a new key with its center at the centroid of its shape
+    pub fn from_shape(label: &str, shape: KeyShape) -> Self {
+        Self::new(label, shape.centroid()).with_shape(shape)
+    }
+
+    /// Set the geometry of the key
+    pub fn with_shape(mut self, shape: KeyShape) -> Self {
+        self.shape = Some(shape);
+        self
+    }
+
+    /// Get the width and the height of the key if its geometry is known
+    pub fn size(&self) -> Option<(f64, f64)> {
+        self.shape.as_ref().map(|shape| {
+            let bounds = shape.bounds();
+            (bounds.width(), bounds.height())
+        })
+    }
+
+    /// Get the bounding box of the key
+    /// Keys without a known geometry only cover their center
+    pub fn bounds(&self) -> Bounds {
+        match &self.shape {
+            Some(shape) => shape.bounds(),
+            None => Bounds::from_points([&self.center]).unwrap(),
+        }
+    }
+}
+
+/// A keyboard layout consisting of named keys with their positions and geometries
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyLayout {
+    name: String,
+    units: Units,
+    keys: Vec<Key>,
+    bounds: Bounds,
+    // Indices of the keys producing each character in the order of the keys
+    char_to_keys: HashMap<char, Vec<usize>>,
+    // Folding table that replaces the one of the options
+    diacritic_folding: Option<HashMap<char, String>>,
+}
+
+impl KeyLayout {
+    /// Create a new layout from its keys
+    /// The layout must have at least one key, all coordinates must be finite and no key may produce a character more
+    /// than once. Several keys may produce the same character.
+    pub fn new(name: &str, keys: Vec<Key>) -> Result<Self, LayoutError> {
+        let mut bounds: Option<Bounds> = None;
+        let mut char_to_keys: HashMap<char, Vec<usize>> = HashMap::new();
+        for (idx, key) in keys.iter().enumerate() {
+            let shape_is_finite = key.shape.as_ref().is_none_or(KeyShape::is_finite);
+            if !key.center.0.is_finite() || !key.center.1.is_finite() || !shape_is_finite {
+                return Err(LayoutError::NonFiniteGeometry {
+                    label: key.label.clone(),
+                });
+            }
+            if !key.shape.as_ref().is_none_or(KeyShape::is_valid) {
+                return Err(LayoutError::InvalidShape {
+                    label: key.label.clone(),
+                });
+            }
+            for &character in &key.chars {
+                let key_indices = char_to_keys.entry(character).or_default();
+                if key_indices.last() == Some(&idx) {
+                    return Err(LayoutError::DuplicateCharacter { character });
+                }
+                key_indices.push(idx);
+            }
+            let key_bounds = key.bounds();
+            bounds = Some(match bounds {
+                Some(bounds) => bounds.union(&key_bounds),
+                None => key_bounds,
+            });
+        }
+        let bounds = bounds.ok_or(LayoutError::NoKeys)?;
+        Ok(Self {
+            name: name.to_string(),
+            units: Units::default(),
+            keys,
+            bounds,
+            char_to_keys,
+            diacritic_folding: None,
+        })
+    }
+
+    /// Create a new layout from the centers of its keys, e.g. from `get_default_buttons_centers()`
+    /// The keys are sorted by their labels
+    pub fn from_centers(name: &str, centers: &HashMap<String, Point>) -> Result<Self, LayoutError> {
+        let mut keys: Vec<Key> = centers
+            .iter()
+            .map(|(label, &center)| Key::new(label, center))
+            .collect();
+        keys.sort_by(|a, b| a.label.cmp(&b.label));
+        Self::new(name, keys)
+    }
+
+    /// Create a new layout from the shapes of its keys
+    /// The center of each key is the centroid of its shape, so it is correct for any shape
+    pub fn from_shapes(name: &str, shapes: Vec<(String, KeyShape)>) -> Result<Self, LayoutError> {
+        let keys = shapes
+            .into_iter()
+            .map(|(label, shape)| Key::from_shape(&label, shape))
+            .collect();
+        Self::new(name, keys)
+    }
+
+    /// Get the name of the layout
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Set the unit of the coordinates
+    pub fn with_units(mut self, units: Units) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Get the unit of the coordinates
+    pub fn units(&self) -> Units {
+        self.units
+    }
+
+    /// Set the bounds of the layout, e.g. if the keyboard has a margin around its keys
+    pub fn with_bounds(mut self, bounds: Bounds) -> Self {
+        self.bounds = bounds;
+        self
+    }
+
+    /// Set the table to fold letters with diacritics that have no key, e.g. a language specific one
+    /// It replaces the table of the options, but folding still has to be enabled with `diacritic_folding`.
+    pub fn with_diacritic_folding(mut self, folding: HashMap<char, String>) -> Self {
+        self.diacritic_folding = Some(folding);
+        self
+    }
+
+    /// Get the folding table of the layout if it has its own
+    pub fn diacritic_folding(&self) -> Option<&HashMap<char, String>> {
+        self.diacritic_folding.as_ref()
+    }
+
+    /// Get all keys of the layout
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    /// Get the bounds of the layout, which are the bounding box of all keys unless they were set explicitly
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    /// Get the key that produces the character
+    /// If several keys produce the character, the first one is returned
+    pub fn key_for_char(&self, character: char) -> Option<&Key> {
+        self.keys_for_char(character).next()
+    }
+
+    /// Get all keys that produce the character in the order of the keys
+    pub fn keys_for_char(&self, character: char) -> impl Iterator<Item = &Key> + '_ {
+        self.char_to_keys
+            .get(&character)
+            .into_iter()
+            .flatten()
+            .map(|&idx| &self.keys[idx])
+    }
+
+    /// Get the centers of the keys that produce a character in the format of `get_default_buttons_centers()`
+    /// Only the first key of characters with several keys is included
+    pub fn to_centers(&self) -> HashMap<String, Point> {
+        self.char_to_keys
+            .iter()
+            .map(|(character, key_indices)| {
+                (character.to_string(), self.keys[key_indices[0]].center)
+            })
+            .collect()
+    }
+}
@@ -1,39 +1,290 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::vec::Vec;
 
+mod aosp;
+mod catalogue;
+mod curve;
+mod error;
+mod export;
+mod folding;
+mod format;
+mod hit_test;
+mod json;
+mod key_instances;
+mod layout;
+mod metrics;
+mod path_iter;
+mod row_layout;
+mod squeekboard;
+mod synth;
 #[cfg(test)]
 mod tests;
+mod timing;
+mod toml;
+mod transform;
+mod xml;
+mod yaml;
 
-pub struct WordPath<'a> {
-    word: Vec<char>,
-    key_layout: &'a HashMap<String, (f64, f64)>,
+pub use catalogue::{builtin_layout_names, get_builtin_layout};
+pub use error::{ImportError, LayoutError, PathError, TransformError};
+pub use folding::default_diacritic_folding;
+pub use format::LAYOUT_FORMAT_VERSION;
+pub use layout::{Bounds, Key, KeyLayout, KeyLookup, KeyShape, Units};
+pub use metrics::{
+    dtw_distance, frechet_distance, proportional_shape_distance, shark2_location_distance,
+    shark2_shape_distance, MetricOptions,
+};
+pub use row_layout::RowLayoutOptions;
+pub use synth::{NoiseConfig, SwipeGenerator};
+pub use timing::{TimedPoint, VelocityProfile, MAX_TIMED_SAMPLES};
+pub use transform::{Padding, Transform, WidgetGeometry, YAxis};
+
+/// A point in the coordinate system of the key layout
+pub type Point = (f64, f64);
+
+/// Shape of the path that connects the waypoints of a word
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathMode {
+    /// Connect the centers of the keys with straight lines
+    Straight,
+    /// Connect the centers of the keys with a centripetal Catmull-Rom spline, which rounds off the corners
+    CatmullRom,
+}
+
+/// What to do with characters of a word that have no key in the layout
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MissingKeyPolicy {
+    /// No path is generated for the word
+    #[default]
+    Fail,
+    /// The character is left out of the path
+    Skip,
+    /// The character is replaced with the characters it is mapped to, e.g. 'ü' with "u" or '\'' with ""
+    /// Characters that are not in the mapping still fail
+    Substitute(HashMap<char, String>),
 }
 
-impl<'a> WordPath<'a> {
+/// Options that control how a word is turned into a path
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WordPathOptions {
+    /// Keep repeated letters like the double "l" in "hello" instead of merging them into one
+    /// Each repetition results in a duplicate waypoint, so the path dwells on the key for one point
+    pub keep_repeated_letters: bool,
+    /// What to do with characters that have no key in the layout
+    pub missing_key_policy: MissingKeyPolicy,
+    /// Table to fold letters with diacritics to their base letters, e.g. `default_diacritic_folding()`
+    /// Only letters that have no key in the layout are folded, so layouts with keys for them are not affected.
+    /// Layouts can replace the table with `KeyLookup::fold_character()`
+    pub diacritic_folding: Option<HashMap<char, String>>,
+}
+
+/// A change that was made to a word because one of its characters has no key in the layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LetterAdjustment {
+    /// The diacritics of the character were removed
+    Folded {
+        character: char,
+        index: usize,
+        folded: String,
+    },
+    /// The character was left out
+    Skipped { character: char, index: usize },
+    /// The character was replaced with the substitute
+    Substituted {
+        character: char,
+        index: usize,
+        substitute: String,
+    },
+}
+
+/// A point of a path together with its position in the word
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathPoint {
+    pub x: f64,
+    pub y: f64,
+    /// Index of the leg the point lies on. The leg i connects the waypoints i and i + 1
+    /// The last waypoint is the end of the last leg and belongs to it
+    pub leg_index: usize,
+    /// Index of the letter in the word if the point is a waypoint
+    pub letter_index: Option<usize>,
+    /// Length of the path from its start to the point
+    pub arc_length: f64,
+}
+
+// The waypoints of a path
+struct Waypoints {
+    points: Vec<Point>,
+    // Index of the letter in the word for each waypoint
+    letter_indices: Vec<usize>,
+    total_length: f64,
+}
+
+impl Waypoints {
+    // Get the waypoint with the given index as a point of the path
+    fn path_point(&self, idx: usize, leg_index: usize, arc_length: f64) -> PathPoint {
+        PathPoint {
+            x: self.points[idx].0,
+            y: self.points[idx].1,
+            leg_index,
+            letter_index: Some(self.letter_indices[idx]),
+            arc_length,
+        }
+    }
+}
+
+/// The ideal path of a word on a keyboard
+///
+/// The key lookup is stored by value. Passing a reference like `&key_layout` borrows the layout, passing an
+/// `Arc<KeyLayout>` results in an owned WordPath that is `Send + Sync` and can be stored in long-lived caches.
+/// Cloning a WordPath is cheap, because the processed word is shared between the clones.
+#[derive(Debug, Clone)]
+pub struct WordPath<L: KeyLookup> {
+    word: Arc<[char]>,
+    // Index of each letter in the word that was passed to the WordPath
+    letter_indices: Arc<[usize]>,
+    adjustments: Arc<[LetterAdjustment]>,
+    // Index of the key position of each letter in the positions returned by `KeyLookup::key_positions()`
+    key_instances: Arc<[usize]>,
+    // Position of the chosen key of each letter, which is None for letters without a key
+    positions: Arc<[Option<Point>]>,
+    key_layout: L,
+}
+
+/// A WordPath that owns a shared layout, which can be sent to other threads and cached
+pub type SharedWordPath = WordPath<Arc<KeyLayout>>;
+
+impl<L: KeyLookup> WordPath<L> {
     /// Create a new WordPath struct
-    pub fn new(key_layout: &'a HashMap<String, (f64, f64)>, word: &str) -> Self {
-        let mut word: Vec<char> = word.chars().collect();
-        word.dedup();
-        // Ignore the case
-        let word: String = word.into_iter().collect::<String>().to_ascii_lowercase();
-        let word: Vec<char> = word.chars().collect();
-        Self { word, key_layout }
+    pub fn new(key_layout: L, word: &str) -> Self {
+        Self::with_options(key_layout, word, &WordPathOptions::default())
+    }
+
+    /// Create a new WordPath struct with the given options
+    pub fn with_options(key_layout: L, word: &str, options: &WordPathOptions) -> Self {
+        let mut letters: Vec<(usize, char)> = Vec::new();
+        let mut adjustments = Vec::new();
+        for (index, character) in word.chars().enumerate() {
+            // Ignore the case. Some characters have a lowercase form that consists of multiple characters
+            for character in character.to_lowercase() {
+                if key_layout.key_position(character).is_some() {
+                    letters.push((index, character));
+                    continue;
+                }
+                // Fold diacritics of characters without a key, e.g. 'é' to 'e'
+                let folded = options
+                    .diacritic_folding
+                    .as_ref()
+                    .and_then(|folding| key_layout.fold_character(character, folding));
+                if let Some(folded) = folded {
+                    adjustments.push(LetterAdjustment::Folded {
+                        character,
+                        index,
+                        folded: folded.clone(),
+                    });
+                    for character in folded.chars() {
+                        if key_layout.key_position(character).is_some() {
+                            letters.push((index, character));
+                        } else {
+                            apply_missing_key_policy(
+                                &options.missing_key_policy,
+                                index,
+                                character,
+                                &mut letters,
+                                &mut adjustments,
+                            );
+                        }
+                    }
+                } else {
+                    apply_missing_key_policy(
+                        &options.missing_key_policy,
+                        index,
+                        character,
+                        &mut letters,
+                        &mut adjustments,
+                    );
+                }
+            }
+        }
+        if !options.keep_repeated_letters {
+            letters.dedup_by_key(|(_, letter)| *letter);
+        }
+        let (letter_indices, word): (Vec<usize>, Vec<char>) = letters.into_iter().unzip();
+        // Look up the positions once, so paths can be generated without looking them up again
+        let mut positions: Vec<Option<Point>> = word
+            .iter()
+            .map(|&letter| key_layout.key_position(letter))
+            .collect();
+        let mut key_instances = vec![0; word.len()];
+        // Choose the keys of letters that are on several keys, which is only needed if there are any
+        if word.iter().any(|&letter| key_layout.key_count(letter) > 1) {
+            let candidates: Vec<Vec<Point>> = word
+                .iter()
+                .map(|&letter| key_layout.key_positions(letter))
+                .collect();
+            key_instances = key_instances::choose_key_instances(&candidates);
+            for (idx, &instance) in key_instances.iter().enumerate() {
+                positions[idx] = candidates[idx].get(instance).copied();
+            }
+        }
+        Self {
+            word: word.into(),
+            letter_indices: letter_indices.into(),
+            adjustments: adjustments.into(),
+            key_instances: key_instances.into(),
+            positions: positions.into(),
+            key_layout,
+        }
+    }
+
+    /// Get the key lookup the path was created with, e.g. to hit test touch points against the same keys
+    pub fn key_layout(&self) -> &L {
+        &self.key_layout
+    }
+
+    /// Get the characters that were folded, skipped or substituted because they have no key in the layout
+    pub fn adjustments(&self) -> &[LetterAdjustment] {
+        &self.adjustments
+    }
+
+    /// Get the key that was chosen for each letter of the path if the layout has several keys for a letter
+    /// Each letter is given by its index in the word and the index of the key in the positions returned by
+    /// `KeyLookup::key_positions()`. The keys are chosen so that the straight path through them is as short as
+    /// possible.
+    pub fn key_instances(&self) -> Vec<(usize, usize)> {
+        self.letter_indices
+            .iter()
+            .copied()
+            .zip(self.key_instances.iter().copied())
+            .collect()
+    }
+
+    // Get the position of the key that was chosen for the letter with the given index in the processed word
+    pub(crate) fn letter_position(&self, idx: usize) -> Option<Point> {
+        self.positions[idx]
+    }
+
+    /// Get the indices of the letters that repeat the letter before them
+    /// There are only repeated letters if they were kept when creating the WordPath
+    pub fn repeated_letter_indices(&self) -> Vec<usize> {
+        (1..self.word.len())
+            .filter(|&idx| self.word[idx] == self.word[idx - 1])
+            .map(|idx| self.letter_indices[idx])
+            .collect()
     }
 
     /// Get the first and the last points of the path
-    pub fn get_first_last_points(&self) -> (Option<&(f64, f64)>, Option<&(f64, f64)>) {
+    pub fn get_first_last_points(&self) -> (Option<Point>, Option<Point>) {
         // Look up the coordinate of the first key
-        let coordinate_first_key = if self.word.len() > 0 {
-            let first_char = self.word[0];
-            self.key_layout.get(&first_char.to_string())
+        let coordinate_first_key = if !self.word.is_empty() {
+            self.letter_position(0)
         } else {
             None
         };
 
         // Look up the coordinate of the last key
-        let coordinate_last_key = if self.word.len() > 0 {
-            let last_char = self.word[self.word.len() - 1];
-            self.key_layout.get(&last_char.to_string())
+        let coordinate_last_key = if !self.word.is_empty() {
+            self.letter_position(self.word.len() - 1)
         } else {
             None
         };
@@ -41,96 +292,258 @@ impl<'a> WordPath<'a> {
         (coordinate_first_key, coordinate_last_key)
     }
 
+    /// Get the waypoints of the path, which are the centers of the keys of the word
+    /// Waypoints of neighbouring letters that share a key are merged into one
+    pub fn get_waypoints(&self) -> Result<Vec<(f64, f64)>, PathError> {
+        Ok(self.ideal_waypoints()?.points)
+    }
+
+    /// Get the total length of the straight lines connecting the waypoints
+    pub fn get_total_length(&self) -> Result<f64, PathError> {
+        Ok(self.ideal_waypoints()?.total_length)
+    }
+
     // Generates a path by connecting the centers of the keys of the word with straight lines. Only the waypoints are returned, nothing is interpolated
     // Waypoints that are the same are merged into one. This happens when multiple characters are sharing the same key
-    // The resulting waypoints and the total length of the path is returned
-    fn ideal_waypoints(&self) -> Option<(Vec<(f64, f64)>, f64)> {
+    // Repeated letters are the exception, they are kept as a duplicate waypoint if they were not removed from the word
+    // The resulting waypoints, the letters they belong to and the total length of the path is returned
+    fn ideal_waypoints(&self) -> Result<Waypoints, PathError> {
+        if self.word.is_empty() {
+            return Err(PathError::EmptyWord);
+        }
         let mut points = Vec::new();
+        let mut letter_indices = Vec::new();
         let mut prev_point = None;
+        let mut prev_letter = None;
         let mut total_length = 0.0;
         let mut leg_length;
-        for letter in &self.word {
-            if let Some(&(x, y)) = self.key_layout.get(&letter.to_string()) {
+        for (idx, letter) in self.word.iter().enumerate() {
+            let is_repeated = prev_letter == Some(letter);
+            prev_letter = Some(letter);
+            if let Some((x, y)) = self.letter_position(idx) {
                 if let Some(prev_point) = prev_point {
                     leg_length = dist(&(x, y), &prev_point);
-                    if leg_length < 0.0000001 {
+                    if leg_length < 0.0000001 && !is_repeated {
                         continue;
                     }
                     total_length += leg_length
                 }
                 prev_point = Some((x, y));
                 points.push((x, y));
+                letter_indices.push(self.letter_indices[idx]);
             } else {
-                return None;
+                return Err(PathError::MissingKey {
+                    character: *letter,
+                    index: self.letter_indices[idx],
+                });
             }
         }
-        Some((points, total_length))
+        Ok(Waypoints {
+            points,
+            letter_indices,
+            total_length,
+        })
     }
 
-    /// Calculate additional points in-between the waypoints
-    fn ideal_path_interpolated(
+    /// Calculate additional points on a Catmull-Rom spline through the waypoints
+    /// The points are spaced by arc length in the same way as the straight path
+    fn ideal_path_curved(
         &self,
-        waypoints: Vec<(f64, f64)>,
+        waypoints: &Waypoints,
         desired_point_density: f64,
-    ) -> Option<Vec<(f64, f64)>> {
-        // If there are no waypoints, we can't construct a path
-        // This can only happen for an empty string (which should never occur)
-        // If the requested desired_point_density is negative, we also can not construct a valid path
-        if waypoints.len() == 0 || desired_point_density < 0.0 {
-            return None;
-        }
-
-        // If there is only one waypoint, we also cannot construct intermediate points
-        if waypoints.len() == 1 {
-            return Some(vec![waypoints[0]; 1]);
+    ) -> Vec<PathPoint> {
+        let points = &waypoints.points;
+        // If there is only one waypoint, we cannot construct intermediate points
+        if points.len() == 1 {
+            return vec![waypoints.path_point(0, 0, 0.0); 1];
         }
 
-        let mut leg_dist;
-        let mut delta_x;
-        let mut delta_y;
-
         let mut remainder = 0.0;
-        let mut path: Vec<(f64, f64)> = Vec::new();
-        let mut waypoints_iter = waypoints.iter().peekable();
+        let mut arc_length = 0.0;
+        let mut path: Vec<PathPoint> = Vec::new();
         let mut no_leg_sections;
-        // While there are more waypoints, interpolate points for them
-        while let Some(start_point) = waypoints_iter.next() {
-            path.push(*start_point);
-            if let Some(&end_point) = waypoints_iter.peek() {
-                leg_dist = dist(start_point, end_point);
-                no_leg_sections = leg_dist / desired_point_density + remainder;
-                remainder = no_leg_sections.fract();
-                no_leg_sections = no_leg_sections.trunc();
-
-                // Calculate the delta and divide it by the number of points
-                // This can also be interpreted as the slope of the linear function connecting the start and the end point
-                delta_x = (end_point.0 - start_point.0) / no_leg_sections;
-                delta_y = (end_point.1 - start_point.1) / no_leg_sections;
-
-                for i in 1..no_leg_sections as isize {
-                    path.push((
-                        delta_x * i as f64 + start_point.0,
-                        delta_y * i as f64 + start_point.1,
-                    ));
-                }
+        for leg in 0..points.len() - 1 {
+            path.push(waypoints.path_point(leg, leg, arc_length));
+            // Approximate the curved leg with a fine polyline to be able to measure its arc length
+            let leg_curve = curve::ArcLengthTable::new(curve::catmull_rom_leg(
+                points,
+                leg,
+                curve::SAMPLES_PER_LEG,
+            ));
+            let leg_dist = leg_curve.length();
+            no_leg_sections = leg_dist / desired_point_density + remainder;
+            remainder = no_leg_sections.fract();
+            no_leg_sections = no_leg_sections.trunc();
+
+            for i in 1..no_leg_sections as isize {
+                let leg_arc_length = leg_dist * i as f64 / no_leg_sections;
+                let (x, y) = leg_curve.point_at(leg_arc_length);
+                path.push(PathPoint {
+                    x,
+                    y,
+                    leg_index: leg,
+                    letter_index: None,
+                    arc_length: arc_length + leg_arc_length,
+                });
+            }
+            arc_length += leg_dist;
+        }
+        path.push(waypoints.path_point(points.len() - 1, points.len() - 2, arc_length));
+        path
+    }
+
+    // Check that a path can be generated for the word without generating it
+    fn check_path(&self, desired_point_density: f64) -> Result<(), PathError> {
+        // If the requested desired_point_density is not positive, we can not construct a valid path
+        if desired_point_density.is_nan() || desired_point_density <= 0.0 {
+            return Err(PathError::InvalidPointDensity(desired_point_density));
+        }
+        if self.word.is_empty() {
+            return Err(PathError::EmptyWord);
+        }
+        for (idx, &letter) in self.word.iter().enumerate() {
+            if self.letter_position(idx).is_none() {
+                return Err(PathError::MissingKey {
+                    character: letter,
+                    index: self.letter_indices[idx],
+                });
             }
         }
-        Some(path)
+        Ok(())
     }
 
     /// Get the ideal path representing a word
-    pub fn get_path(&self, desired_point_density: f64) -> Option<Vec<(f64, f64)>> {
-        // Get waypoints
-        let ideal_path = self.ideal_waypoints();
+    pub fn get_path(&self, desired_point_density: f64) -> Result<Vec<(f64, f64)>, PathError> {
+        Ok(self.path_iter(desired_point_density)?.collect())
+    }
+
+    /// Get the ideal path representing a word as an iterator
+    /// The points are calculated while iterating, so no memory is allocated
+    pub fn path_iter(
+        &self,
+        desired_point_density: f64,
+    ) -> Result<impl Iterator<Item = Point> + '_, PathError> {
+        self.check_path(desired_point_density)?;
+        Ok(path_iter::PathIter::new(self, desired_point_density).map(|point| (point.x, point.y)))
+    }
+
+    /// Get the ideal path representing a word and write it into the buffer
+    /// The buffer is cleared first, so it can be reused for multiple words without allocating memory each time
+    pub fn get_path_into(
+        &self,
+        desired_point_density: f64,
+        path: &mut Vec<Point>,
+    ) -> Result<(), PathError> {
+        path.clear();
+        path.extend(self.path_iter(desired_point_density)?);
+        Ok(())
+    }
+
+    /// Get the ideal path representing a word with the waypoints connected as specified by the mode
+    pub fn get_path_with_mode(
+        &self,
+        desired_point_density: f64,
+        mode: PathMode,
+    ) -> Result<Vec<(f64, f64)>, PathError> {
+        match mode {
+            PathMode::Straight => self.get_path(desired_point_density),
+            PathMode::CatmullRom => {
+                let path = self.get_annotated_path(desired_point_density, mode)?;
+                Ok(path.into_iter().map(|point| (point.x, point.y)).collect())
+            }
+        }
+    }
+
+    /// Get the ideal path representing a word with each point annotated with its position in the word
+    pub fn get_annotated_path(
+        &self,
+        desired_point_density: f64,
+        mode: PathMode,
+    ) -> Result<Vec<PathPoint>, PathError> {
+        self.check_path(desired_point_density)?;
         // Interpolate the path
-        if let Some((ideal_path, _)) = ideal_path {
-            self.ideal_path_interpolated(ideal_path, desired_point_density)
-        } else {
-            None
+        Ok(match mode {
+            PathMode::Straight => path_iter::PathIter::new(self, desired_point_density).collect(),
+            PathMode::CatmullRom => {
+                let waypoints = self.ideal_waypoints()?;
+                self.ideal_path_curved(&waypoints, desired_point_density)
+            }
+        })
+    }
+
+    /// Get the ideal path representing a word resampled to exactly no_points points
+    /// The points are evenly spaced along the straight lines connecting the waypoints and include both ends of the path
+    pub fn get_path_resampled(&self, no_points: usize) -> Result<Vec<(f64, f64)>, PathError> {
+        if no_points == 0 {
+            return Err(PathError::InvalidPointCount(no_points));
+        }
+        let Waypoints {
+            points: waypoints,
+            total_length,
+            ..
+        } = self.ideal_waypoints()?;
+        // If all points are on the same spot, they are all equidistant
+        if waypoints.len() == 1 {
+            return Ok(vec![waypoints[0]; no_points]);
+        }
+        // A single point can't include both ends of the path
+        if no_points == 1 {
+            return Err(PathError::InvalidPointCount(no_points));
+        }
+
+        let last_point = waypoints[waypoints.len() - 1];
+        let polyline = curve::ArcLengthTable::new(waypoints);
+        let mut path: Vec<(f64, f64)> = (0..no_points - 1)
+            .map(|i| polyline.point_at(total_length * i as f64 / (no_points - 1) as f64))
+            .collect();
+        path.push(last_point);
+        Ok(path)
+    }
+
+    /// Get the ideal path representing a word with the time at which each point is reached
+    /// The movement is sampled every sample_interval seconds and timed according to the velocity profile
+    /// Paths that would need more than `MAX_TIMED_SAMPLES` samples are rejected
+    pub fn get_timed_path(
+        &self,
+        sample_interval: f64,
+        velocity_profile: &VelocityProfile,
+    ) -> Result<Vec<TimedPoint>, PathError> {
+        // Without a positive sample interval, time would never pass
+        if sample_interval.is_nan() || sample_interval <= 0.0 {
+            return Err(PathError::InvalidSampleInterval(sample_interval));
+        }
+        let waypoints = self.ideal_waypoints()?;
+        timing::sample_min_jerk(&waypoints.points, sample_interval, velocity_profile)
+    }
+}
+
+// Apply the policy for a character without a key
+fn apply_missing_key_policy(
+    policy: &MissingKeyPolicy,
+    index: usize,
+    character: char,
+    letters: &mut Vec<(usize, char)>,
+    adjustments: &mut Vec<LetterAdjustment>,
+) {
+    match policy {
+        MissingKeyPolicy::Skip => {
+            adjustments.push(LetterAdjustment::Skipped { character, index });
+        }
+        MissingKeyPolicy::Substitute(fallback) if fallback.contains_key(&character) => {
+            let substitute = fallback[&character].clone();
+            letters.extend(substitute.chars().map(|letter| (index, letter)));
+            adjustments.push(LetterAdjustment::Substituted {
+                character,
+                index,
+                substitute,
+            });
         }
+        _ => letters.push((index, character)),
     }
 }
 
+/// Calculate the centers of the buttons by averaging their raw coordinates
+/// Use `KeyLayout::to_rust_source()` to turn the result into code
 pub fn get_button_centers() -> HashMap<std::string::String, (f64, f64)> {
     let buttons_raw = get_buttons();
     // Get sum up the coordinates of the buttons
@@ -152,18 +565,13 @@ pub fn get_button_centers() -> HashMap<std::string::String, (f64, f64)> {
         let button_y = button_y / button_count as f64;
         buttons_coordinates_normalized.insert(button_id.to_string(), (button_x, button_y));
     }
-    for (button_name, (x, y)) in buttons_coordinates_normalized.iter() {
-        println!(
-            "buttons.push((\"{}\".to_string(), {:.3}, {:.3}));",
-            button_name, x, y,
-        );
-    }
-
     buttons_coordinates_normalized
 }
 
+// Only uses basic arithmetic and sqrt, so the result is the same on every platform
 fn dist(start: &(f64, f64), end: &(f64, f64)) -> f64 {
-    f64::sqrt((start.0 - end.0).powi(2) + (start.1 - end.1).powi(2))
+    let (dx, dy) = (start.0 - end.0, start.1 - end.1);
+    f64::sqrt(dx * dx + dy * dy)
 }
 
 pub fn get_default_buttons_centers() -> HashMap<String, (f64, f64)> {
@@ -197,6 +605,25 @@ pub fn get_default_buttons_centers() -> HashMap<String, (f64, f64)> {
     buttons
 }
 
+/// Get the default layout with the keys of `get_default_buttons_centers()`
+/// Each key is a square with a side length of 0.1
+pub fn get_default_layout() -> KeyLayout {
+    let centers = get_default_buttons_centers();
+    let mut keys: Vec<Key> = centers
+        .iter()
+        .map(|(label, &(x, y))| {
+            Key::new(label, (x, y)).with_shape(KeyShape::Rect {
+                x: x - 0.05,
+                y: y - 0.05,
+                width: 0.1,
+                height: 0.1,
+            })
+        })
+        .collect();
+    keys.sort_by(|a, b| a.label.cmp(&b.label));
+    KeyLayout::new("default", keys).expect("the default layout is valid")
+}
+
 fn get_buttons() -> Vec<(String, f64, f64)> {
     vec![
         ("m".to_string(), 0.725, 0.125),
@@ -6,14 +6,15 @@ fn main() {
     let word_path = WordPath::new(&key_layout, word);
     let ideal_path = word_path.get_path(0.1);
 
-    if let Some(ideal_path) = ideal_path {
-        // Print the path for debugging
-        println!("Path for '{}':", word);
-        for (i, point) in ideal_path.iter().enumerate() {
-            println!("{:.3}, {:.3}", point.0, point.1);
-            println!("NO: {}, x: {:.3}, y: {:.3}", i, point.0, point.1);
+    match ideal_path {
+        Ok(ideal_path) => {
+            // Print the path for debugging
+            println!("Path for '{}':", word);
+            for (i, point) in ideal_path.iter().enumerate() {
+                println!("{:.3}, {:.3}", point.0, point.1);
+                println!("NO: {}, x: {:.3}, y: {:.3}", i, point.0, point.1);
+            }
         }
-    } else {
-        println!("There is no valid path possible");
+        Err(error) => println!("There is no valid path possible: {}", error),
     }
 }
@@ -0,0 +1,221 @@
+use crate::{curve, dist, Bounds, KeyLookup, PathError, Point, WordPath};
+
+/// Options of the metrics that compare a user's swipe with the ideal path of a word
+///
+/// The user path has to be in the coordinates of the layout of the word, e.g. touch points mapped with the inverse
+/// of `Transform::normalized_to_pixels()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricOptions {
+    /// Number of equidistant points both paths are resampled to, SHARK2 uses 100
+    pub no_points: usize,
+    /// Length of the longer side of the bounding box of both paths after normalizing them for the shape channel
+    pub normalized_size: f64,
+    /// Radius of the tunnel around the paths in layout units, e.g. half the width of a key
+    /// The location channel is 0 if each path stays within the tunnel around the other one
+    pub tunnel_radius: f64,
+}
+
+impl Default for MetricOptions {
+    fn default() -> Self {
+        Self {
+            no_points: 100,
+            normalized_size: 1.0,
+            tunnel_radius: 0.05,
+        }
+    }
+}
+
+/// Dynamic time warping distance between the user path and the ideal path of the word
+///
+/// The points of the user path are matched to the points of the ideal path, which is resampled to `no_points`
+/// equidistant points, in order, but each point may be matched to several points of the other path. The distance is
+/// the sum of the distances of the matched points divided by the number of points of both paths, so it does not grow
+/// with the number of points.
+pub fn dtw_distance<L: KeyLookup>(
+    user_path: &[Point],
+    template: &WordPath<L>,
+    options: &MetricOptions,
+) -> Result<f64, PathError> {
+    let template_path = template_points(user_path, template, options)?;
+    // Costs of the cheapest matches of the user path so far with each prefix of the ideal path
+    let mut prev_costs = vec![f64::INFINITY; template_path.len()];
+    let mut costs = vec![0.0; template_path.len()];
+    for (user_idx, user_point) in user_path.iter().enumerate() {
+        for (idx, template_point) in template_path.iter().enumerate() {
+            let cheapest_prefix = match (user_idx, idx) {
+                (0, 0) => 0.0,
+                (0, _) => costs[idx - 1],
+                (_, 0) => prev_costs[0],
+                _ => prev_costs[idx].min(prev_costs[idx - 1]).min(costs[idx - 1]),
+            };
+            costs[idx] = cheapest_prefix + dist(user_point, template_point);
+        }
+        std::mem::swap(&mut prev_costs, &mut costs);
+    }
+    Ok(prev_costs[template_path.len() - 1] / (user_path.len() + template_path.len()) as f64)
+}
+
+/// Discrete Fréchet distance between the user path and the ideal path of the word
+///
+/// It is the shortest leash that is needed to walk along the points of both paths in order, where the ideal path is
+/// resampled to `no_points` equidistant points. In contrast to the other metrics, a single point that is far off
+/// results in a large distance.
+pub fn frechet_distance<L: KeyLookup>(
+    user_path: &[Point],
+    template: &WordPath<L>,
+    options: &MetricOptions,
+) -> Result<f64, PathError> {
+    let template_path = template_points(user_path, template, options)?;
+    // Shortest leashes that are needed to reach each point of the ideal path with the current point of the user path
+    let mut prev_leashes = vec![f64::INFINITY; template_path.len()];
+    let mut leashes = vec![0.0; template_path.len()];
+    for (user_idx, user_point) in user_path.iter().enumerate() {
+        for (idx, template_point) in template_path.iter().enumerate() {
+            let shortest_prefix = match (user_idx, idx) {
+                (0, 0) => 0.0,
+                (0, _) => leashes[idx - 1],
+                (_, 0) => prev_leashes[0],
+                _ => prev_leashes[idx]
+                    .min(prev_leashes[idx - 1])
+                    .min(leashes[idx - 1]),
+            };
+            leashes[idx] = f64::max(shortest_prefix, dist(user_point, template_point));
+        }
+        std::mem::swap(&mut prev_leashes, &mut leashes);
+    }
+    Ok(prev_leashes[template_path.len() - 1])
+}
+
+/// Proportional shape matching distance between the user path and the ideal path of the word
+///
+/// Both paths are resampled to `no_points` equidistant points and the distance is the average distance between the
+/// points with the same index. Unlike the dynamic time warping distance, the speed of the swipe does not matter.
+pub fn proportional_shape_distance<L: KeyLookup>(
+    user_path: &[Point],
+    template: &WordPath<L>,
+    options: &MetricOptions,
+) -> Result<f64, PathError> {
+    let (user_points, template_points) = resampled_paths(user_path, template, options)?;
+    Ok(average_distance(&user_points, &template_points))
+}
+
+/// Distance of the shape channel of SHARK2 between the user path and the ideal path of the word
+///
+/// Both paths are resampled to `no_points` equidistant points and normalized, so that the centroid of their points is
+/// at the origin and the longer side of their bounding box is `normalized_size` long. The distance is the average
+/// distance between the points with the same index, so it only depends on the shape of the paths, but not on their
+/// location or size.
+pub fn shark2_shape_distance<L: KeyLookup>(
+    user_path: &[Point],
+    template: &WordPath<L>,
+    options: &MetricOptions,
+) -> Result<f64, PathError> {
+    let (user_points, template_points) = resampled_paths(user_path, template, options)?;
+    Ok(average_distance(
+        &normalize(&user_points, options.normalized_size),
+        &normalize(&template_points, options.normalized_size),
+    ))
+}
+
+/// Distance of the location channel of SHARK2 between the user path and the ideal path of the word
+///
+/// Both paths are resampled to `no_points` equidistant points. If every point of each path is within `tunnel_radius`
+/// of a point of the other path, the distance is 0. Otherwise it is the weighted average distance between the points
+/// with the same index. The weights decrease linearly from both ends towards the middle, because the start and the
+/// end of a swipe are the most precise.
+pub fn shark2_location_distance<L: KeyLookup>(
+    user_path: &[Point],
+    template: &WordPath<L>,
+    options: &MetricOptions,
+) -> Result<f64, PathError> {
+    let (user_points, template_points) = resampled_paths(user_path, template, options)?;
+    if is_within_tunnel(&user_points, &template_points, options.tunnel_radius)
+        && is_within_tunnel(&template_points, &user_points, options.tunnel_radius)
+    {
+        return Ok(0.0);
+    }
+    let middle = (user_points.len() - 1) as f64 / 2.0;
+    let weights: Vec<f64> = (0..user_points.len())
+        .map(|idx| (idx as f64 - middle).abs() + 1.0)
+        .collect();
+    let total_weight: f64 = weights.iter().sum();
+    Ok(user_points
+        .iter()
+        .zip(&template_points)
+        .zip(&weights)
+        .map(|((user_point, template_point), weight)| weight * dist(user_point, template_point))
+        .sum::<f64>()
+        / total_weight)
+}
+
+// Check the user path and resample the ideal path of the word to the number of points of the options
+fn template_points<L: KeyLookup>(
+    user_path: &[Point],
+    template: &WordPath<L>,
+    options: &MetricOptions,
+) -> Result<Vec<Point>, PathError> {
+    if user_path.is_empty() {
+        return Err(PathError::EmptyUserPath);
+    }
+    if options.no_points < 2 {
+        return Err(PathError::InvalidPointCount(options.no_points));
+    }
+    template.get_path_resampled(options.no_points)
+}
+
+// Resample both paths to the number of points of the options
+fn resampled_paths<L: KeyLookup>(
+    user_path: &[Point],
+    template: &WordPath<L>,
+    options: &MetricOptions,
+) -> Result<(Vec<Point>, Vec<Point>), PathError> {
+    let template_path = template_points(user_path, template, options)?;
+    let polyline = curve::ArcLengthTable::new(user_path.to_vec());
+    let total_length = polyline.length();
+    let mut user_points: Vec<Point> = (0..options.no_points - 1)
+        .map(|i| polyline.point_at(total_length * i as f64 / (options.no_points - 1) as f64))
+        .collect();
+    user_points.push(user_path[user_path.len() - 1]);
+    Ok((user_points, template_path))
+}
+
+// Average distance between the points with the same index
+fn average_distance(path: &[Point], other_path: &[Point]) -> f64 {
+    path.iter()
+        .zip(other_path)
+        .map(|(point, other_point)| dist(point, other_point))
+        .sum::<f64>()
+        / path.len() as f64
+}
+
+// Move the centroid of the points to the origin and scale them, so that the longer side of their bounding box has the
+// given size. Points that all lie on the same spot are only moved
+fn normalize(path: &[Point], size: f64) -> Vec<Point> {
+    let bounds = Bounds::from_points(path).expect("the path was resampled to at least two points");
+    let longer_side = bounds.width().max(bounds.height());
+    let scale = if longer_side > 0.0 {
+        size / longer_side
+    } else {
+        1.0
+    };
+    let no_points = path.len() as f64;
+    let centroid_x = path.iter().map(|point| point.0).sum::<f64>() / no_points;
+    let centroid_y = path.iter().map(|point| point.1).sum::<f64>() / no_points;
+    path.iter()
+        .map(|point| {
+            (
+                (point.0 - centroid_x) * scale,
+                (point.1 - centroid_y) * scale,
+            )
+        })
+        .collect()
+}
+
+// Check if every point of the path is within the radius of a point of the other path
+fn is_within_tunnel(path: &[Point], other_path: &[Point], radius: f64) -> bool {
+    path.iter().all(|point| {
+        other_path
+            .iter()
+            .any(|other_point| dist(point, other_point) <= radius)
+    })
+}
@@ -0,0 +1,133 @@
+use crate::{dist, KeyLookup, PathPoint, Point, WordPath};
+
+// A straight leg between two waypoints that is divided into sections of equal length
+struct Leg {
+    start: Point,
+    delta_x: f64,
+    delta_y: f64,
+    no_sections: isize,
+    length: f64,
+    arc_length_start: f64,
+}
+
+/// Iterator over the points of the straight path of a word
+/// The waypoints are looked up and interpolated one after the other, so no intermediate buffers are needed
+/// The word must have been checked to only contain letters with keys before the iterator is created
+pub(crate) struct PathIter<'w, L: KeyLookup> {
+    word_path: &'w WordPath<L>,
+    desired_point_density: f64,
+    // Position of the next letter of the word that is looked up
+    letter_pos: usize,
+    prev_letter: Option<char>,
+    prev_waypoint: Option<Point>,
+    // The waypoint that is returned after the current leg is finished
+    pending_waypoint: Option<(Point, usize)>,
+    leg: Option<Leg>,
+    section: isize,
+    no_legs: usize,
+    remainder: f64,
+    arc_length: f64,
+}
+
+impl<'w, L: KeyLookup> PathIter<'w, L> {
+    pub(crate) fn new(word_path: &'w WordPath<L>, desired_point_density: f64) -> Self {
+        let mut path_iter = Self {
+            word_path,
+            desired_point_density,
+            letter_pos: 0,
+            prev_letter: None,
+            prev_waypoint: None,
+            pending_waypoint: None,
+            leg: None,
+            section: 0,
+            no_legs: 0,
+            remainder: 0.0,
+            arc_length: 0.0,
+        };
+        path_iter.pending_waypoint = path_iter.next_waypoint();
+        path_iter
+    }
+
+    // Look up the next waypoint and the index of its letter in the word
+    // Waypoints that are the same are merged into one unless they belong to a repeated letter
+    fn next_waypoint(&mut self) -> Option<(Point, usize)> {
+        while self.letter_pos < self.word_path.word.len() {
+            let idx = self.letter_pos;
+            let letter = self.word_path.word[idx];
+            self.letter_pos += 1;
+            let is_repeated = self.prev_letter == Some(letter);
+            self.prev_letter = Some(letter);
+            let point = self
+                .word_path
+                .letter_position(idx)
+                .expect("the word was checked to only contain letters with keys");
+            if let Some(prev_waypoint) = self.prev_waypoint {
+                if dist(&point, &prev_waypoint) < 0.0000001 && !is_repeated {
+                    continue;
+                }
+            }
+            self.prev_waypoint = Some(point);
+            return Some((point, self.word_path.letter_indices[idx]));
+        }
+        None
+    }
+}
+
+impl<L: KeyLookup> Iterator for PathIter<'_, L> {
+    type Item = PathPoint;
+
+    fn next(&mut self) -> Option<PathPoint> {
+        // Return the points in-between the waypoints of the current leg
+        if let Some(leg) = &self.leg {
+            if self.section < leg.no_sections {
+                let i = self.section;
+                self.section += 1;
+                return Some(PathPoint {
+                    x: leg.delta_x * i as f64 + leg.start.0,
+                    y: leg.delta_y * i as f64 + leg.start.1,
+                    leg_index: self.no_legs - 1,
+                    letter_index: None,
+                    arc_length: leg.arc_length_start
+                        + leg.length * i as f64 / leg.no_sections as f64,
+                });
+            }
+            self.leg = None;
+        }
+
+        // The current leg is finished, so its end is returned and the next leg starts there
+        let (start_point, letter_index) = self.pending_waypoint.take()?;
+        let arc_length = self.arc_length;
+        let leg_index = if let Some((end_point, end_letter_index)) = self.next_waypoint() {
+            let leg_dist = dist(&start_point, &end_point);
+            let mut no_leg_sections = leg_dist / self.desired_point_density + self.remainder;
+            self.remainder = no_leg_sections.fract();
+            no_leg_sections = no_leg_sections.trunc();
+
+            // Calculate the delta and divide it by the number of points
+            // This can also be interpreted as the slope of the linear function connecting the start and the end point
+            self.leg = Some(Leg {
+                start: start_point,
+                delta_x: (end_point.0 - start_point.0) / no_leg_sections,
+                delta_y: (end_point.1 - start_point.1) / no_leg_sections,
+                no_sections: no_leg_sections as isize,
+                length: leg_dist,
+                arc_length_start: arc_length,
+            });
+            self.section = 1;
+            self.arc_length += leg_dist;
+            self.pending_waypoint = Some((end_point, end_letter_index));
+            self.no_legs += 1;
+            self.no_legs - 1
+        } else {
+            // The last waypoint belongs to the last leg
+            self.no_legs.saturating_sub(1)
+        };
+        Some(PathPoint {
+            x: start_point.0,
+            y: start_point.1,
+            leg_index,
+            letter_index: Some(letter_index),
+            arc_length,
+        })
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{Key, KeyLayout, KeyShape, LayoutError};
+
+/// Geometry of a layout that is built from rows of characters by `KeyLayout::from_rows()`
+/// The default options reproduce `get_default_layout()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowLayoutOptions {
+    /// Width of each key
+    pub key_width: f64,
+    /// Height of each key
+    pub key_height: f64,
+    /// Horizontal gap between two keys of a row
+    pub horizontal_gap: f64,
+    /// Vertical gap between two rows
+    pub vertical_gap: f64,
+    /// Horizontal offset of each row from the top, e.g. to stagger the rows
+    /// Rows without an offset start at x = 0
+    pub row_offsets: Vec<f64>,
+}
+
+impl Default for RowLayoutOptions {
+    fn default() -> Self {
+        Self {
+            key_width: 0.1,
+            key_height: 0.1,
+            horizontal_gap: 0.0,
+            vertical_gap: 0.0,
+            row_offsets: vec![0.0, 0.05, 0.1],
+        }
+    }
+}
+
+impl KeyLayout {
+    /// Create a layout from rows of characters
+    ///
+    /// Each character of a row becomes a rectangular key that produces the character, whitespace is ignored. The
+    /// keys of a row are placed from left to right and the rows from top to bottom, both starting at 0.
+    pub fn from_rows(
+        name: &str,
+        rows: &[&str],
+        options: &RowLayoutOptions,
+    ) -> Result<Self, LayoutError> {
+        let mut keys = Vec::new();
+        for (row_idx, row) in rows.iter().enumerate() {
+            let offset = options.row_offsets.get(row_idx).copied().unwrap_or(0.0);
+            let y = row_idx as f64 * (options.key_height + options.vertical_gap);
+            let characters = row.chars().filter(|character| !character.is_whitespace());
+            for (column, character) in characters.enumerate() {
+                keys.push(Key::from_shape(
+                    &character.to_string(),
+                    KeyShape::Rect {
+                        x: offset + column as f64 * (options.key_width + options.horizontal_gap),
+                        y,
+                        width: options.key_width,
+                        height: options.key_height,
+                    },
+                ));
+            }
+        }
+        Self::new(name, keys)
+    }
+
+    /// Create a layout from a string of rows separated by '/', e.g. "qwertyuiop / asdfghjkl / zxcvbnm"
+    /// Use `KeyLayout::from_rows()` for layouts with a key for '/'.
+    pub fn from_row_string(
+        name: &str,
+        rows: &str,
+        options: &RowLayoutOptions,
+    ) -> Result<Self, LayoutError> {
+        let rows: Vec<&str> = rows.split('/').collect();
+        Self::from_rows(name, &rows, options)
+    }
+}
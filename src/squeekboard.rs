@@ -0,0 +1,135 @@
+use crate::yaml::{self, Yaml};
+use crate::{ImportError, Key, KeyLayout, KeyShape};
+
+// Outline of buttons that don't name one
+const DEFAULT_OUTLINE: &str = "default";
+
+impl KeyLayout {
+    /// Load a view of a layout file of Squeekboard
+    ///
+    /// Each row of the view is a string of button names separated by whitespace. The size of a button is given by
+    /// its outline. The buttons of a row are placed next to each other and the rows are centered horizontally and
+    /// stacked from the top, so the y axis points down like in `get_default_layout()`. The layout is scaled to a width
+    /// of 1.0 while keeping its aspect ratio.
+    ///
+    /// A button produces the `text` of its entry in `buttons` or otherwise its name, if that is a single character.
+    /// Its label is the `label` of its entry or otherwise its name. The layout is named after the view.
+    pub fn from_squeekboard_yaml(source: &str, view: &str) -> Result<Self, ImportError> {
+        let document = yaml::parse(source)?;
+        let rows = document
+            .get("views")
+            .ok_or_else(|| ImportError::missing_field("views"))?
+            .get(view)
+            .ok_or_else(|| ImportError::missing_field(&format!("views.{}", view)))?
+            .as_sequence()
+            .ok_or_else(|| {
+                ImportError::invalid_value(&format!("views.{}", view), "not a sequence")
+            })?;
+
+        // Place the buttons of each row next to each other, starting at x = 0
+        let mut placed_rows: Vec<Vec<(Key, f64, f64)>> = Vec::with_capacity(rows.len());
+        for (row_idx, row) in rows.iter().enumerate() {
+            let row_field = format!("views.{}[{}]", view, row_idx);
+            let row = row
+                .as_str()
+                .ok_or_else(|| ImportError::invalid_value(&row_field, "not a string"))?;
+            let mut placed_row = Vec::new();
+            for name in row.split_whitespace() {
+                let button = document
+                    .get("buttons")
+                    .and_then(|buttons| buttons.get(name));
+                let outline = match button.and_then(|button| button.get("outline")) {
+                    Some(outline) => outline.as_str().ok_or_else(|| {
+                        ImportError::invalid_value(
+                            &format!("buttons.{}.outline", name),
+                            "not a string",
+                        )
+                    })?,
+                    None => DEFAULT_OUTLINE,
+                };
+                let (width, height) = outline_size(&document, outline)?;
+                let text = string_field(button, name, "text")?.unwrap_or(name);
+                let mut key = Key::new(text, (0.0, 0.0));
+                key.label = string_field(button, name, "label")?
+                    .unwrap_or(name)
+                    .to_string();
+                placed_row.push((key, width, height));
+            }
+            placed_rows.push(placed_row);
+        }
+
+        let row_width =
+            |row: &Vec<(Key, f64, f64)>| row.iter().map(|(_, width, _)| width).sum::<f64>();
+        let view_width = placed_rows.iter().map(row_width).fold(0.0, f64::max);
+        let scale = if view_width > 0.0 {
+            1.0 / view_width
+        } else {
+            1.0
+        };
+
+        let mut keys = Vec::new();
+        let mut y = 0.0;
+        for row in placed_rows {
+            let mut x = (view_width - row_width(&row)) / 2.0;
+            let row_height = row.iter().map(|(_, _, height)| *height).fold(0.0, f64::max);
+            for (key, width, height) in row {
+                // Buttons that are lower than their row are centered vertically
+                let shape = KeyShape::Rect {
+                    x: x * scale,
+                    y: (y + (row_height - height) / 2.0) * scale,
+                    width: width * scale,
+                    height: height * scale,
+                };
+                keys.push(Key {
+                    center: shape.centroid(),
+                    shape: Some(shape),
+                    ..key
+                });
+                x += width;
+            }
+            y += row_height;
+        }
+        Ok(Self::new(view, keys)?)
+    }
+}
+
+// Get the width and the height of an outline
+// Older layout files give the size in a nested `bounds` mapping
+fn outline_size(document: &Yaml, outline: &str) -> Result<(f64, f64), ImportError> {
+    let field = format!("outlines.{}", outline);
+    let outline = document
+        .get("outlines")
+        .and_then(|outlines| outlines.get(outline))
+        .ok_or_else(|| ImportError::missing_field(&field))?;
+    let (outline, field) = match outline.get("bounds") {
+        Some(bounds) => (bounds, format!("{}.bounds", field)),
+        None => (outline, field),
+    };
+    let dimension = |name: &str| -> Result<f64, ImportError> {
+        let dimension_field = format!("{}.{}", field, name);
+        let value = outline
+            .get(name)
+            .ok_or_else(|| ImportError::missing_field(&dimension_field))?
+            .as_str()
+            .ok_or_else(|| ImportError::invalid_value(&dimension_field, "not a number"))?;
+        match value.parse::<f64>() {
+            Ok(dimension) if dimension.is_finite() && dimension >= 0.0 => Ok(dimension),
+            _ => Err(ImportError::invalid_value(&dimension_field, value)),
+        }
+    };
+    Ok((dimension("width")?, dimension("height")?))
+}
+
+// Get an optional string field of a button
+fn string_field<'d>(
+    button: Option<&'d Yaml>,
+    name: &str,
+    field: &str,
+) -> Result<Option<&'d str>, ImportError> {
+    match button.and_then(|button| button.get(field)) {
+        Some(value) => value.as_str().map(Some).ok_or_else(|| {
+            ImportError::invalid_value(&format!("buttons.{}.{}", name, field), "not a string")
+        }),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,222 @@
+use crate::timing::sample_min_jerk;
+use crate::{KeyLookup, PathError, TimedPoint, VelocityProfile, WordPath};
+
+/// Parameters of the noise that is added to synthetic swipes
+/// All random values are drawn from normal distributions with the given standard deviations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NoiseConfig {
+    /// Standard deviation of the jitter added to each point in layout units
+    pub point_jitter: f64,
+    /// Standard deviation of the offset of each waypoint from the center of its key in layout units
+    pub key_offset: f64,
+    /// Standard deviation of the translation of the whole swipe in layout units
+    pub translation: f64,
+    /// Standard deviation of the scale factor of the whole swipe around 1.0
+    pub scale: f64,
+    /// Standard deviation of the rotation of the whole swipe in radians
+    pub rotation: f64,
+    /// Standard deviation of the speed factor of the whole swipe around 1.0
+    pub speed: f64,
+    /// Time in seconds between two points of the swipe
+    pub sample_interval: f64,
+    /// Motor model of the swipe before the speed is varied
+    pub velocity_profile: VelocityProfile,
+}
+
+impl Default for NoiseConfig {
+    fn default() -> Self {
+        Self {
+            point_jitter: 0.002,
+            key_offset: 0.012,
+            translation: 0.01,
+            scale: 0.05,
+            rotation: 0.03,
+            speed: 0.15,
+            sample_interval: 0.01,
+            velocity_profile: VelocityProfile::default(),
+        }
+    }
+}
+
+impl NoiseConfig {
+    /// A config that adds no noise at all
+    pub fn noiseless() -> Self {
+        Self {
+            point_jitter: 0.0,
+            key_offset: 0.0,
+            translation: 0.0,
+            scale: 0.0,
+            rotation: 0.0,
+            speed: 0.0,
+            ..Self::default()
+        }
+    }
+}
+
+/// Generator of synthetic swipes that perturbs ideal paths with noise
+///
+/// The generator is deterministic: Two generators created with the same config and seed produce the same swipes for
+/// the same sequence of words on every platform. To achieve this, it only uses basic arithmetic and sqrt, which are
+/// exactly specified by IEEE 754, and does not rely on the platform's implementation of ln, sin or cos.
+#[derive(Debug, Clone)]
+pub struct SwipeGenerator {
+    config: NoiseConfig,
+    rng: SplitMix64,
+}
+
+impl SwipeGenerator {
+    /// Create a new generator
+    pub fn new(config: NoiseConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: SplitMix64::new(seed),
+        }
+    }
+
+    /// Get the config of the generator
+    pub fn config(&self) -> &NoiseConfig {
+        &self.config
+    }
+
+    /// Generate a synthetic swipe for the word
+    /// An error is returned if no ideal path exists for the word
+    pub fn generate<L: KeyLookup>(
+        &mut self,
+        word_path: &WordPath<L>,
+    ) -> Result<Vec<TimedPoint>, PathError> {
+        let sample_interval = self.config.sample_interval;
+        if sample_interval.is_nan() || sample_interval <= 0.0 {
+            return Err(PathError::InvalidSampleInterval(sample_interval));
+        }
+        let mut waypoints = word_path.ideal_waypoints()?.points;
+
+        // Miss the center of each key a little
+        for waypoint in waypoints.iter_mut() {
+            waypoint.0 += self.config.key_offset * self.rng.next_gaussian();
+            waypoint.1 += self.config.key_offset * self.rng.next_gaussian();
+        }
+
+        // Draw the swipe faster or slower. The speed factor must not become negative
+        // A factor that is not finite is kept, so the resulting profile is rejected instead of hanging
+        self.config.velocity_profile.check()?;
+        let speed_factor = 1.0 + self.config.speed * self.rng.next_gaussian();
+        let speed_factor = if speed_factor.is_finite() {
+            speed_factor.max(0.1)
+        } else {
+            speed_factor
+        };
+        let velocity_profile = VelocityProfile {
+            leg_time_offset: self.config.velocity_profile.leg_time_offset / speed_factor,
+            leg_time_slope: self.config.velocity_profile.leg_time_slope / speed_factor,
+        };
+        let mut swipe = sample_min_jerk(&waypoints, sample_interval, &velocity_profile)?;
+
+        // Scale and rotate the swipe around its center, then move it
+        let scale_factor = (1.0 + self.config.scale * self.rng.next_gaussian()).max(0.1);
+        let (sin, cos) = sin_cos(self.config.rotation * self.rng.next_gaussian());
+        let translation = (
+            self.config.translation * self.rng.next_gaussian(),
+            self.config.translation * self.rng.next_gaussian(),
+        );
+        let center = (
+            waypoints.iter().map(|point| point.0).sum::<f64>() / waypoints.len() as f64,
+            waypoints.iter().map(|point| point.1).sum::<f64>() / waypoints.len() as f64,
+        );
+        for point in swipe.iter_mut() {
+            let (dx, dy) = (point.x - center.0, point.y - center.1);
+            point.x = center.0 + scale_factor * (cos * dx - sin * dy) + translation.0;
+            point.y = center.1 + scale_factor * (sin * dx + cos * dy) + translation.1;
+
+            // Add jitter to every single point
+            point.x += self.config.point_jitter * self.rng.next_gaussian();
+            point.y += self.config.point_jitter * self.rng.next_gaussian();
+        }
+        Ok(swipe)
+    }
+}
+
+/// SplitMix64 pseudo random number generator (Steele, Lea & Flood, 2014)
+#[derive(Debug, Clone)]
+pub(crate) struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniformly distributed number in [0, 1)
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        // Use the upper 53 bits, which is the precision of a f64
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Normally distributed number with mean 0 and standard deviation 1
+    /// Uses Marsaglia's polar method, which only needs ln and sqrt
+    pub(crate) fn next_gaussian(&mut self) -> f64 {
+        loop {
+            let u = 2.0 * self.next_f64() - 1.0;
+            let v = 2.0 * self.next_f64() - 1.0;
+            let s = u * u + v * v;
+            if s > 0.0 && s < 1.0 {
+                return u * (-2.0 * ln(s) / s).sqrt();
+            }
+        }
+    }
+}
+
+/// Natural logarithm of a positive, finite number that only uses basic arithmetic
+pub(crate) fn ln(x: f64) -> f64 {
+    const LN_2: f64 = std::f64::consts::LN_2;
+    // Split x into a mantissa m in [1, 2) and an exponent e, so that x = m * 2^e
+    let bits = x.to_bits();
+    let mut exponent = ((bits >> 52) & 0x7ff) as i64 - 1023;
+    let mut mantissa = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | 0x3ff0_0000_0000_0000);
+    if exponent == -1023 {
+        // Subnormal numbers have no implicit leading bit
+        return ln(x * (1u64 << 54) as f64) - 54.0 * LN_2;
+    }
+    // Move the mantissa to [sqrt(1/2), sqrt(2)) to speed up the convergence of the series
+    if mantissa > std::f64::consts::SQRT_2 {
+        mantissa /= 2.0;
+        exponent += 1;
+    }
+    // ln(m) = 2 * atanh(z) = 2 * (z + z^3/3 + z^5/5 + ...) with z = (m - 1) / (m + 1)
+    let z = (mantissa - 1.0) / (mantissa + 1.0);
+    let z_squared = z * z;
+    let mut term = z;
+    let mut sum = 0.0;
+    for k in 0..20 {
+        sum += term / (2 * k + 1) as f64;
+        term *= z_squared;
+    }
+    exponent as f64 * LN_2 + 2.0 * sum
+}
+
+/// Sine and cosine of an angle that only use basic arithmetic
+pub(crate) fn sin_cos(angle: f64) -> (f64, f64) {
+    use std::f64::consts::{PI, TAU};
+    // Move the angle to [-PI, PI]
+    let angle = angle - TAU * ((angle + PI) / TAU).floor();
+    // Taylor series, which converge quickly enough on this interval
+    let angle_squared = angle * angle;
+    let mut sin_term = angle;
+    let mut cos_term = 1.0;
+    let (mut sin, mut cos) = (0.0, 0.0);
+    for k in 0..15 {
+        sin += sin_term;
+        cos += cos_term;
+        sin_term *= -angle_squared / ((2 * k + 2) * (2 * k + 3)) as f64;
+        cos_term *= -angle_squared / ((2 * k + 1) * (2 * k + 2)) as f64;
+    }
+    (sin, cos)
+}
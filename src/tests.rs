@@ -12,7 +12,7 @@ fn test_empty_word() {
     let word_path = WordPath::new(&key_layout, word);
 
     // Check the interpolated path
-    assert!(word_path.get_path(0.1).is_none());
+    assert!(word_path.get_path(0.1) == Err(PathError::EmptyWord));
 }
 
 #[test]
@@ -23,7 +23,8 @@ fn test_negative_point_density() {
     let word_path = WordPath::new(&key_layout, word);
 
     // Check the interpolated path
-    assert!(word_path.get_path(-0.1).is_none());
+    assert!(word_path.get_path(-0.1) == Err(PathError::InvalidPointDensity(-0.1)));
+    assert!(word_path.get_path(0.0) == Err(PathError::InvalidPointDensity(0.0)));
 }
 
 #[test]
@@ -38,7 +39,14 @@ fn test_no_path_possible() {
     assert!(first_word.is_none() && last_word.is_none());
 
     // Check the interpolated path
-    assert!(word_path.get_path(-0.1).is_none());
+    assert!(word_path.get_path(-0.1) == Err(PathError::InvalidPointDensity(-0.1)));
+    assert!(
+        word_path.get_path(0.1)
+            == Err(PathError::MissingKey {
+                character: 'ü',
+                index: 0
+            })
+    );
 }
 
 #[test]
@@ -49,8 +57,7 @@ fn test_no_path_possible_but_first_last_valid() {
     let word_path = WordPath::new(&key_layout, word);
 
     // Check first and last points
-    if let (Some(&(first_x, first_y)), Some(&(last_x, last_y))) = word_path.get_first_last_points()
-    {
+    if let (Some((first_x, first_y)), Some((last_x, last_y))) = word_path.get_first_last_points() {
         assert!(float_cmp(first_x, 0.600) && float_cmp(first_y, 0.15));
         assert!(float_cmp(last_x, 0.850) && float_cmp(last_y, 0.05));
     } else {
@@ -58,7 +65,14 @@ fn test_no_path_possible_but_first_last_valid() {
     }
 
     // Check the interpolated path
-    assert!(word_path.get_path(-0.1).is_none());
+    assert!(word_path.get_path(-0.1) == Err(PathError::InvalidPointDensity(-0.1)));
+    assert!(
+        word_path.get_path(0.1)
+            == Err(PathError::MissingKey {
+                character: 'ü',
+                index: 1
+            })
+    );
 }
 
 #[test]
@@ -67,14 +81,13 @@ fn test_word_i() {
     let word = "I";
     println!("Path for '{}':", word);
 
-    let correct_path = vec![(0.750, 0.05)];
+    let correct_path = [(0.750, 0.05)];
 
     let key_layout = get_default_buttons_centers();
     let word_path = WordPath::new(&key_layout, word);
 
     // Check the first and last points
-    if let (Some(&(first_x, first_y)), Some(&(last_x, last_y))) = word_path.get_first_last_points()
-    {
+    if let (Some((first_x, first_y)), Some((last_x, last_y))) = word_path.get_first_last_points() {
         assert!(float_cmp(first_x, 0.750) && float_cmp(first_y, 0.05));
         assert!(float_cmp(last_x, 0.750) && float_cmp(last_y, 0.05));
     } else {
@@ -83,7 +96,7 @@ fn test_word_i() {
 
     // Check the interpolated path
     let ideal_path = word_path.get_path(0.1);
-    if let Some(ideal_path) = ideal_path {
+    if let Ok(ideal_path) = ideal_path {
         assert!(ideal_path.len() == correct_path.len());
         for (idx, &(point_x, point_y)) in ideal_path.iter().enumerate() {
             assert!(
@@ -120,8 +133,7 @@ fn test_word_hello() {
     let word_path = WordPath::new(&key_layout, word);
 
     // Check the first and last points
-    if let (Some(&(first_x, first_y)), Some(&(last_x, last_y))) = word_path.get_first_last_points()
-    {
+    if let (Some((first_x, first_y)), Some((last_x, last_y))) = word_path.get_first_last_points() {
         assert!(float_cmp(first_x, 0.600) && float_cmp(first_y, 0.15));
         assert!(float_cmp(last_x, 0.850) && float_cmp(last_y, 0.05));
     } else {
@@ -130,7 +142,7 @@ fn test_word_hello() {
 
     // Check the interpolated path
     let ideal_path = word_path.get_path(0.1);
-    if let Some(ideal_path) = ideal_path {
+    if let Ok(ideal_path) = ideal_path {
         assert!(ideal_path.len() == correct_path.len());
         for (idx, &(point_x, point_y)) in ideal_path.iter().enumerate() {
             println!("({},{}),", point_x, point_y);
@@ -301,8 +313,7 @@ fn test_word_hello_dense() {
     let word_path = WordPath::new(&key_layout, word);
 
     // Check the first and last points
-    if let (Some(&(first_x, first_y)), Some(&(last_x, last_y))) = word_path.get_first_last_points()
-    {
+    if let (Some((first_x, first_y)), Some((last_x, last_y))) = word_path.get_first_last_points() {
         assert!(float_cmp(first_x, 0.600) && float_cmp(first_y, 0.15));
         assert!(float_cmp(last_x, 0.850) && float_cmp(last_y, 0.05));
     } else {
@@ -311,7 +322,7 @@ fn test_word_hello_dense() {
 
     // Check the interpolated path
     let ideal_path = word_path.get_path(0.01);
-    if let Some(ideal_path) = ideal_path {
+    if let Ok(ideal_path) = ideal_path {
         assert!(ideal_path.len() == correct_path.len());
         for (idx, &(point_x, point_y)) in ideal_path.iter().enumerate() {
             println!("({},{}),", point_x, point_y);
@@ -367,8 +378,7 @@ fn test_word_spaceship() {
     let word_path = WordPath::new(&key_layout, word);
 
     // Check the first and last points
-    if let (Some(&(first_x, first_y)), Some(&(last_x, last_y))) = word_path.get_first_last_points()
-    {
+    if let (Some((first_x, first_y)), Some((last_x, last_y))) = word_path.get_first_last_points() {
         assert!(float_cmp(first_x, 0.200) && float_cmp(first_y, 0.15));
         assert!(float_cmp(last_x, 0.950) && float_cmp(last_y, 0.05));
     } else {
@@ -377,7 +387,7 @@ fn test_word_spaceship() {
 
     // Check the interpolated path
     let ideal_path = word_path.get_path(0.1);
-    if let Some(ideal_path) = ideal_path {
+    if let Ok(ideal_path) = ideal_path {
         //assert!(ideal_path.len() == correct_path.len());
         for (idx, &(point_x, point_y)) in ideal_path.iter().enumerate() {
             println!("({},{}),", point_x, point_y);
@@ -389,3 +399,1957 @@ fn test_word_spaceship() {
         panic!();
     }
 }
+
+#[test]
+// Test case B10
+fn test_word_family_curved() {
+    let word = "family";
+    let desired_point_density = 0.01;
+
+    let key_layout = get_default_buttons_centers();
+    let word_path = WordPath::new(&key_layout, word);
+
+    // The straight mode has to be the same as the default path
+    assert!(word_path.get_path(0.1) == word_path.get_path_with_mode(0.1, PathMode::Straight));
+
+    let curved_path = word_path
+        .get_path_with_mode(desired_point_density, PathMode::CatmullRom)
+        .unwrap();
+    let straight_path = word_path.get_path(desired_point_density).unwrap();
+
+    // The curve starts and ends on the first and the last key
+    let (first, last) = (curved_path[0], curved_path[curved_path.len() - 1]);
+    assert!(float_cmp(first.0, 0.400) && float_cmp(first.1, 0.15));
+    assert!(float_cmp(last.0, 0.550) && float_cmp(last.1, 0.05));
+
+    // The curve passes through the centers of all keys
+    for key in ['a', 'm', 'i', 'l'] {
+        let &(key_x, key_y) = key_layout.get(&key.to_string()).unwrap();
+        assert!(curved_path
+            .iter()
+            .any(|&(x, y)| float_cmp(x, key_x) && float_cmp(y, key_y)));
+    }
+
+    // The points are spaced roughly as requested and the curve is longer than the straight path
+    let mut curved_length = 0.0;
+    for points in curved_path.windows(2) {
+        let section = dist(&points[0], &points[1]);
+        assert!(section < desired_point_density * 1.5);
+        curved_length += section;
+    }
+    let straight_length: f64 = straight_path
+        .windows(2)
+        .map(|points| dist(&points[0], &points[1]))
+        .sum();
+    assert!(curved_length > straight_length);
+    assert!((curved_path.len() as isize - straight_path.len() as isize).abs() < 20);
+}
+
+#[test]
+// Test case B11
+fn test_word_hello_timed() {
+    let word = "hello";
+    let sample_interval = 0.01;
+    let velocity_profile = VelocityProfile::default();
+
+    let key_layout = get_default_buttons_centers();
+    let word_path = WordPath::new(&key_layout, word);
+    assert!(
+        word_path.get_timed_path(0.0, &velocity_profile)
+            == Err(PathError::InvalidSampleInterval(0.0))
+    );
+
+    // Legs must take a finite time that is not negative and the path must take some time
+    let invalid_profile = |leg_time_offset: f64, leg_time_slope: f64| VelocityProfile {
+        leg_time_offset,
+        leg_time_slope,
+    };
+    assert!(matches!(
+        word_path.get_timed_path(sample_interval, &invalid_profile(f64::NAN, 0.3)),
+        Err(PathError::InvalidVelocityProfile { .. })
+    ));
+    assert!(
+        word_path.get_timed_path(sample_interval, &invalid_profile(-1.0, 0.3))
+            == Err(PathError::InvalidVelocityProfile {
+                leg_time_offset: -1.0,
+                leg_time_slope: 0.3
+            })
+    );
+    assert!(
+        word_path.get_timed_path(sample_interval, &invalid_profile(0.08, -0.3))
+            == Err(PathError::InvalidVelocityProfile {
+                leg_time_offset: 0.08,
+                leg_time_slope: -0.3
+            })
+    );
+    assert!(
+        word_path.get_timed_path(sample_interval, &invalid_profile(0.0, 0.0))
+            == Err(PathError::InvalidVelocityProfile {
+                leg_time_offset: 0.0,
+                leg_time_slope: 0.0
+            })
+    );
+    assert!(
+        word_path.get_timed_path(sample_interval, &invalid_profile(f64::MAX, 0.3))
+            == Err(PathError::InvalidVelocityProfile {
+                leg_time_offset: f64::MAX,
+                leg_time_slope: 0.3
+            })
+    );
+    // A single leg with a huge but finite duration or a tiny sample interval needs too many samples
+    let single_leg = WordPath::new(&key_layout, "ab");
+    assert!(
+        single_leg.get_timed_path(sample_interval, &invalid_profile(f64::MAX, 0.3))
+            == Err(PathError::TooManySamples(f64::INFINITY))
+    );
+    assert!(matches!(
+        single_leg.get_timed_path(1e-12, &velocity_profile),
+        Err(PathError::TooManySamples(no_samples)) if no_samples > MAX_TIMED_SAMPLES as f64
+    ));
+    // A single key takes no time
+    let single_key = WordPath::new(&key_layout, "a");
+    let timed_path = single_key
+        .get_timed_path(sample_interval, &velocity_profile)
+        .unwrap();
+    assert!(
+        timed_path
+            == vec![TimedPoint {
+                x: 0.1,
+                y: 0.15,
+                t: 0.0
+            }]
+    );
+
+    let timed_path = word_path
+        .get_timed_path(sample_interval, &velocity_profile)
+        .unwrap();
+
+    // The path starts on the first key at time 0 and ends on the last key after all legs are drawn
+    let (first, last) = (timed_path[0], timed_path[timed_path.len() - 1]);
+    assert!(float_cmp(first.x, 0.600) && float_cmp(first.y, 0.15) && float_cmp(first.t, 0.0));
+    assert!(float_cmp(last.x, 0.850) && float_cmp(last.y, 0.05));
+    let total_time = velocity_profile.leg_duration(dist(&(0.6, 0.15), &(0.25, 0.05)))
+        + velocity_profile.leg_duration(dist(&(0.25, 0.05), &(0.9, 0.15)))
+        + velocity_profile.leg_duration(dist(&(0.9, 0.15), &(0.85, 0.05)));
+    assert!(float_cmp(last.t, total_time));
+
+    // The points are sampled at the requested interval
+    for points in timed_path[..timed_path.len() - 1].windows(2) {
+        assert!(float_cmp(points[1].t - points[0].t, sample_interval));
+    }
+
+    // The finger is slow at the corner on the "e" key and fastest in the middle of the long leg from "e" to "l"
+    let speeds: Vec<f64> = timed_path
+        .windows(2)
+        .map(|points| {
+            dist(&(points[0].x, points[0].y), &(points[1].x, points[1].y))
+                / (points[1].t - points[0].t)
+        })
+        .collect();
+    let (fastest, &max_speed) = speeds
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+        .unwrap();
+    let corner = timed_path
+        .iter()
+        .position(|point| point.x < 0.26 && point.y < 0.06)
+        .unwrap();
+    assert!(speeds[corner] < max_speed / 4.0);
+    assert!(timed_path[fastest].x > 0.4 && timed_path[fastest].x < 0.75);
+}
+
+#[test]
+// Test case B12
+fn test_word_hello_resampled() {
+    let key_layout = get_default_buttons_centers();
+    let word_path = WordPath::new(&key_layout, "hello");
+    assert!(word_path.get_path_resampled(0) == Err(PathError::InvalidPointCount(0)));
+    assert!(word_path.get_path_resampled(1) == Err(PathError::InvalidPointCount(1)));
+
+    for no_points in [2, 3, 17, 100] {
+        let path = word_path.get_path_resampled(no_points).unwrap();
+        assert!(path.len() == no_points);
+        assert!(float_cmp(path[0].0, 0.600) && float_cmp(path[0].1, 0.15));
+        assert!(float_cmp(path[no_points - 1].0, 0.850) && float_cmp(path[no_points - 1].1, 0.05));
+    }
+
+    // The straight sections between the points are all equally long, so the points are evenly spaced
+    // The only exceptions are the sections that cut the corners at the waypoints
+    let path = word_path.get_path_resampled(50).unwrap();
+    let section_lengths: Vec<f64> = path
+        .windows(2)
+        .map(|points| dist(&points[0], &points[1]))
+        .collect();
+    let longest = section_lengths.iter().cloned().fold(0.0, f64::max);
+    let no_equal = section_lengths
+        .iter()
+        .filter(|&&length| float_cmp(length, longest))
+        .count();
+    assert!(no_equal >= section_lengths.len() - 3);
+
+    // A word with a single key has all its points on that key
+    let word_path = WordPath::new(&key_layout, "I");
+    let path = word_path.get_path_resampled(5).unwrap();
+    assert!(path.len() == 5 && path.iter().all(|&point| point == (0.750, 0.05)));
+}
+
+#[test]
+// Test case B13
+fn test_deterministic_math() {
+    // Reference values of SplitMix64 for the seed 1234567
+    let mut rng = synth::SplitMix64::new(1234567);
+    assert!(rng.next_u64() == 6457827717110365317);
+    assert!(rng.next_u64() == 3203168211198807973);
+    assert!(rng.next_u64() == 9817491932198370423);
+
+    for x in [1e-300, 0.001, 0.3, 0.5, 0.99, 1.0, 1.5, 2.0, 7.0, 1e10] {
+        assert!((synth::ln(x) - x.ln()).abs() <= 1e-14 * x.ln().abs().max(1.0));
+    }
+    for angle in [-10.0, -3.0, -0.5, 0.0, 0.01, 1.0, 3.1, 6.0, 100.0] {
+        let (sin, cos) = synth::sin_cos(angle);
+        assert!((sin - f64::sin(angle)).abs() < 1e-12 && (cos - f64::cos(angle)).abs() < 1e-12);
+    }
+}
+
+#[test]
+// Test case B14
+fn test_synthetic_swipes() {
+    let key_layout = get_default_buttons_centers();
+    let word_path = WordPath::new(&key_layout, "family");
+
+    // Without noise, the synthetic swipe is the ideal timed path
+    let mut generator = SwipeGenerator::new(NoiseConfig::noiseless(), 42);
+    let config = *generator.config();
+    let swipe = generator.generate(&word_path).unwrap();
+    let ideal_path = word_path
+        .get_timed_path(config.sample_interval, &config.velocity_profile)
+        .unwrap();
+    assert!(swipe.len() == ideal_path.len());
+    for (point, ideal_point) in swipe.iter().zip(ideal_path.iter()) {
+        assert!(float_cmp(point.x, ideal_point.x) && float_cmp(point.y, ideal_point.y));
+        assert!(point.t == ideal_point.t);
+    }
+
+    // The same seed results in the same swipes, a different seed in different ones
+    let mut generator_a = SwipeGenerator::new(NoiseConfig::default(), 42);
+    let mut generator_b = SwipeGenerator::new(NoiseConfig::default(), 42);
+    let mut generator_c = SwipeGenerator::new(NoiseConfig::default(), 43);
+    for _ in 0..3 {
+        let swipe_a = generator_a.generate(&word_path).unwrap();
+        let swipe_b = generator_b.generate(&word_path).unwrap();
+        let swipe_c = generator_c.generate(&word_path).unwrap();
+        assert!(swipe_a == swipe_b);
+        assert!(swipe_a != swipe_c);
+
+        // The swipe still starts close to the first key
+        assert!(dist(&(swipe_a[0].x, swipe_a[0].y), &(0.400, 0.15)) < 0.1);
+    }
+
+    // Invalid velocity profiles and speeds are rejected instead of hanging
+    let mut config = NoiseConfig::default();
+    config.velocity_profile.leg_time_offset = f64::NAN;
+    assert!(matches!(
+        SwipeGenerator::new(config, 42).generate(&word_path),
+        Err(PathError::InvalidVelocityProfile { .. })
+    ));
+    config.velocity_profile.leg_time_offset = -1.0;
+    assert!(matches!(
+        SwipeGenerator::new(config, 42).generate(&word_path),
+        Err(PathError::InvalidVelocityProfile { .. })
+    ));
+    for speed in [f64::NAN, f64::INFINITY] {
+        let config = NoiseConfig {
+            speed,
+            ..NoiseConfig::default()
+        };
+        assert!(matches!(
+            SwipeGenerator::new(config, 42).generate(&word_path),
+            Err(PathError::InvalidVelocityProfile { .. })
+        ));
+    }
+
+    // Tiny sample intervals need too many samples
+    let config = NoiseConfig {
+        sample_interval: 1e-12,
+        ..NoiseConfig::default()
+    };
+    assert!(matches!(
+        SwipeGenerator::new(config, 42).generate(&word_path),
+        Err(PathError::TooManySamples(_))
+    ));
+
+    // There is no swipe for a word without an ideal path
+    let word_path = WordPath::new(&key_layout, "ÜÜÜ");
+    assert!(matches!(
+        generator_a.generate(&word_path),
+        Err(PathError::MissingKey { .. })
+    ));
+}
+
+#[test]
+// Test case B15
+fn test_keep_repeated_letters() {
+    let key_layout = get_default_buttons_centers();
+    let options = WordPathOptions {
+        keep_repeated_letters: true,
+        ..WordPathOptions::default()
+    };
+
+    // By default repeated letters are merged
+    let word_path_hello = WordPath::new(&key_layout, "hello");
+    let word_path_helo = WordPath::new(&key_layout, "helo");
+    assert!(word_path_hello.repeated_letter_indices().is_empty());
+    assert!(word_path_hello.get_path(0.1) == word_path_helo.get_path(0.1));
+
+    // If they are kept, the path dwells on the repeated key
+    let word_path_hello = WordPath::with_options(&key_layout, "HeLlo", &options);
+    let word_path_helo = WordPath::with_options(&key_layout, "helo", &options);
+    assert!(word_path_hello.repeated_letter_indices() == vec![3]);
+    assert!(word_path_helo.repeated_letter_indices().is_empty());
+
+    let path_hello = word_path_hello.get_path(0.1).unwrap();
+    let path_helo = word_path_helo.get_path(0.1).unwrap();
+    assert!(path_hello.len() == path_helo.len() + 1);
+    let dwell = path_hello
+        .windows(2)
+        .position(|points| points[0] == points[1])
+        .unwrap();
+    assert!(path_hello[dwell] == (0.9, 0.15));
+    assert!(path_hello[..=dwell] == path_helo[..=dwell]);
+    assert!(path_hello[dwell + 1..] == path_helo[dwell..]);
+
+    // Different letters that share a key are still merged
+    let mut key_layout = key_layout;
+    key_layout.insert("ł".to_string(), (0.9, 0.15));
+    let word_path = WordPath::with_options(&key_layout, "hełlo", &options);
+    assert!(word_path.get_path(0.1) == Ok(path_helo));
+}
+
+#[test]
+// Test case B16
+fn test_missing_key_policy() {
+    let key_layout = get_default_buttons_centers();
+
+    // By default a missing key fails
+    let word_path = WordPath::new(&key_layout, "don't");
+    assert!(
+        word_path.get_path(0.1)
+            == Err(PathError::MissingKey {
+                character: '\'',
+                index: 3
+            })
+    );
+    assert!(word_path.adjustments().is_empty());
+
+    // The character can be skipped
+    let options = WordPathOptions {
+        missing_key_policy: MissingKeyPolicy::Skip,
+        ..WordPathOptions::default()
+    };
+    let word_path = WordPath::with_options(&key_layout, "don't", &options);
+    assert!(word_path.get_path(0.1) == WordPath::new(&key_layout, "dont").get_path(0.1));
+    assert!(
+        word_path.adjustments()
+            == [LetterAdjustment::Skipped {
+                character: '\'',
+                index: 3
+            }]
+    );
+
+    // The character can be substituted, characters without a fallback still fail
+    let mut fallback = HashMap::new();
+    fallback.insert('ü', "u".to_string());
+    let options = WordPathOptions {
+        missing_key_policy: MissingKeyPolicy::Substitute(fallback),
+        ..WordPathOptions::default()
+    };
+    let word_path = WordPath::with_options(&key_layout, "hÜÜÜÜo", &options);
+    assert!(word_path.get_path(0.1) == WordPath::new(&key_layout, "huo").get_path(0.1));
+    assert!(word_path.adjustments().len() == 4);
+    assert!(
+        word_path.adjustments()[1]
+            == LetterAdjustment::Substituted {
+                character: 'ü',
+                index: 2,
+                substitute: "u".to_string()
+            }
+    );
+    let word_path = WordPath::with_options(&key_layout, "don't", &options);
+    assert!(matches!(
+        word_path.get_path(0.1),
+        Err(PathError::MissingKey {
+            character: '\'',
+            ..
+        })
+    ));
+}
+
+#[test]
+// Test case B17
+fn test_unicode_folding() {
+    let key_layout = get_default_buttons_centers();
+
+    // Non-ASCII letters are lowercased as well
+    let word_path = WordPath::new(&key_layout, "ÜBER");
+    assert!(matches!(
+        word_path.get_path(0.1),
+        Err(PathError::MissingKey {
+            character: 'ü',
+            index: 0
+        })
+    ));
+
+    // Letters with diacritics are folded to the base letter if the option is set
+    let options = WordPathOptions {
+        diacritic_folding: Some(default_diacritic_folding()),
+        ..WordPathOptions::default()
+    };
+    for (word, folded_word) in [
+        ("Über", "uber"),
+        ("CAFÉ", "cafe"),
+        ("cafe\u{301}", "cafe"),
+        ("straße", "strase"),
+        ("Ærø", "aero"),
+    ] {
+        let word_path = WordPath::with_options(&key_layout, word, &options);
+        assert!(word_path.get_path(0.1) == WordPath::new(&key_layout, folded_word).get_path(0.1));
+    }
+    let word_path = WordPath::with_options(&key_layout, "café", &options);
+    assert!(
+        word_path.adjustments()
+            == [LetterAdjustment::Folded {
+                character: 'é',
+                index: 3,
+                folded: "e".to_string()
+            }]
+    );
+
+    // Letters that have a key in the layout are not folded
+    let mut key_layout_german = key_layout.clone();
+    key_layout_german.insert("ü".to_string(), (0.5, 0.3));
+    let word_path = WordPath::with_options(&key_layout_german, "über", &options);
+    assert!(word_path.get_first_last_points().0 == Some((0.5, 0.3)));
+    assert!(word_path.adjustments().is_empty());
+
+    // Layouts can replace the folding table, e.g. a German layout folds 'ø' to its 'ö' key
+    let key_layout_qwertz = get_builtin_layout("qwertz").unwrap();
+    let word_path = WordPath::with_options(&key_layout_qwertz, "Größe", &options);
+    assert!(word_path.get_path(0.1) == WordPath::new(&key_layout_qwertz, "grösse").get_path(0.1));
+    let german_folding: HashMap<char, String> = [('ø', "ö"), ('ß', "ss")]
+        .into_iter()
+        .map(|(character, folded)| (character, folded.to_string()))
+        .collect();
+    let key_layout_qwertz = key_layout_qwertz.with_diacritic_folding(german_folding.clone());
+    assert!(key_layout_qwertz.diacritic_folding() == Some(&german_folding));
+    let word_path = WordPath::with_options(&key_layout_qwertz, "Ørsted", &options);
+    assert!(word_path.get_path(0.1) == WordPath::new(&key_layout_qwertz, "örsted").get_path(0.1));
+    assert!(word_path.get_first_last_points().0 == key_layout_qwertz.key_position('ö'));
+    let word_path = WordPath::with_options(&key_layout_qwertz, "Straße", &options);
+    assert!(word_path.get_path(0.1) == WordPath::new(&key_layout_qwertz, "strasse").get_path(0.1));
+    // Letters that are not in the table of the layout are not folded
+    let word_path = WordPath::with_options(&key_layout_qwertz, "café", &options);
+    assert!(matches!(
+        word_path.get_path(0.1),
+        Err(PathError::MissingKey {
+            character: 'é',
+            index: 3
+        })
+    ));
+    // Folding still has to be enabled in the options
+    let word_path = WordPath::new(&key_layout_qwertz, "Ørsted");
+    assert!(word_path.get_path(0.1).is_err());
+    // The folding table of the layout is part of its files
+    assert!(KeyLayout::from_json(&key_layout_qwertz.to_json()).unwrap() == key_layout_qwertz);
+    assert!(KeyLayout::from_toml(&key_layout_qwertz.to_toml()).unwrap() == key_layout_qwertz);
+    let empty_folding = key_layout_qwertz
+        .clone()
+        .with_diacritic_folding(HashMap::new());
+    assert!(KeyLayout::from_toml(&empty_folding.to_toml()).unwrap() == empty_folding);
+    let source = r#"{ "version": 1, "name": "folding", "diacritic_folding": { "ß": "ss", "ø": "ö" },
+        "keys": [{ "label": "s", "center": [0.1, 0.1] }, { "label": "ö", "center": [0.2, 0.1] }] }"#;
+    let word_path = WordPath::with_options(KeyLayout::from_json(source).unwrap(), "øß", &options);
+    assert!(word_path.get_first_last_points() == (Some((0.2, 0.1)), Some((0.1, 0.1))));
+    for (folding, field) in [
+        (r#"["ß"]"#, "diacritic_folding"),
+        (r#"{ "ss": "s" }"#, "diacritic_folding"),
+        (r#"{ "ß": 1 }"#, "diacritic_folding.ß"),
+    ] {
+        let source = format!(
+            r#"{{ "version": 1, "name": "folding", "diacritic_folding": {}, "keys": [{{ "label": "s", "center": [0.1, 0.1] }}] }}"#,
+            folding
+        );
+        assert!(matches!(
+            KeyLayout::from_json(&source),
+            Err(ImportError::InvalidValue { field: error_field, .. }) if error_field == field
+        ));
+    }
+
+    // Greek capital letters are lowercased and their accents removed
+    let mut key_layout_greek = HashMap::new();
+    key_layout_greek.insert("α".to_string(), (0.1, 0.1));
+    key_layout_greek.insert("σ".to_string(), (0.2, 0.1));
+    key_layout_greek.insert("ο".to_string(), (0.3, 0.1));
+    let word_path = WordPath::with_options(&key_layout_greek, "ΣΆΣ", &options);
+    assert!(word_path.get_path(0.1).is_ok());
+}
+
+#[test]
+// Test case B18
+fn test_waypoints_and_total_length() {
+    let key_layout = get_default_buttons_centers();
+    let word_path = WordPath::new(&key_layout, "hello");
+
+    let waypoints = word_path.get_waypoints().unwrap();
+    assert!(waypoints == vec![(0.6, 0.15), (0.25, 0.05), (0.9, 0.15), (0.85, 0.05)]);
+    let total_length = word_path.get_total_length().unwrap();
+    let correct_length = dist(&(0.6, 0.15), &(0.25, 0.05))
+        + dist(&(0.25, 0.05), &(0.9, 0.15))
+        + dist(&(0.9, 0.15), &(0.85, 0.05));
+    assert!(float_cmp(total_length, correct_length));
+
+    let word_path = WordPath::new(&key_layout, "I");
+    assert!(word_path.get_waypoints() == Ok(vec![(0.75, 0.05)]));
+    assert!(word_path.get_total_length() == Ok(0.0));
+
+    let word_path = WordPath::new(&key_layout, "");
+    assert!(word_path.get_waypoints() == Err(PathError::EmptyWord));
+    assert!(word_path.get_total_length() == Err(PathError::EmptyWord));
+}
+
+#[test]
+// Test case B19
+fn test_annotated_path() {
+    let key_layout = get_default_buttons_centers();
+    let word_path = WordPath::new(&key_layout, "hello");
+
+    for mode in [PathMode::Straight, PathMode::CatmullRom] {
+        let annotated_path = word_path.get_annotated_path(0.1, mode).unwrap();
+        let path = word_path.get_path_with_mode(0.1, mode).unwrap();
+        assert!(annotated_path.len() == path.len());
+        for (point, &(x, y)) in annotated_path.iter().zip(path.iter()) {
+            assert!(point.x == x && point.y == y);
+        }
+
+        // The waypoints are annotated with the letters of the word. The "l" is the fourth letter, because the first
+        // one is merged with the second one
+        let waypoints: Vec<(usize, Option<usize>)> = annotated_path
+            .iter()
+            .filter(|point| point.letter_index.is_some())
+            .map(|point| (point.leg_index, point.letter_index))
+            .collect();
+        assert!(waypoints == vec![(0, Some(0)), (1, Some(1)), (2, Some(2)), (2, Some(4))]);
+
+        // The legs and the arc length never decrease
+        for points in annotated_path.windows(2) {
+            assert!(points[0].leg_index <= points[1].leg_index);
+            assert!(points[0].arc_length < points[1].arc_length);
+        }
+    }
+
+    // The arc length of the straight path ends with the total length of the path
+    let annotated_path = word_path
+        .get_annotated_path(0.1, PathMode::Straight)
+        .unwrap();
+    let last = annotated_path[annotated_path.len() - 1];
+    assert!(float_cmp(
+        last.arc_length,
+        word_path.get_total_length().unwrap()
+    ));
+    let point = annotated_path[5];
+    assert!(point.leg_index == 1 && point.letter_index.is_none());
+    assert!(float_cmp(
+        point.arc_length,
+        dist(&(0.6, 0.15), &(0.25, 0.05)) + dist(&(0.25, 0.05), &(point.x, point.y))
+    ));
+}
+
+#[test]
+// Test case B20
+fn test_path_iter() {
+    let key_layout = get_default_buttons_centers();
+
+    let mut buffer = Vec::new();
+    for word in ["hello", "spaceship", "family", "I"] {
+        let word_path = WordPath::new(&key_layout, word);
+        let path = word_path.get_path(0.01).unwrap();
+        let path_iter: Vec<Point> = word_path.path_iter(0.01).unwrap().collect();
+        assert!(path == path_iter);
+
+        // The buffer is reused
+        word_path.get_path_into(0.01, &mut buffer).unwrap();
+        assert!(path == buffer);
+    }
+    let capacity = buffer.capacity();
+    let word_path = WordPath::new(&key_layout, "I");
+    word_path.get_path_into(0.01, &mut buffer).unwrap();
+    assert!(buffer == vec![(0.75, 0.05)] && buffer.capacity() == capacity);
+
+    // Errors are found before iterating
+    let word_path = WordPath::new(&key_layout, "hÜÜÜÜo");
+    assert!(matches!(
+        word_path.path_iter(0.1),
+        Err(PathError::MissingKey { index: 1, .. })
+    ));
+    assert!(word_path.get_path_into(0.1, &mut buffer).is_err());
+    let word_path = WordPath::new(&key_layout, "");
+    assert!(matches!(
+        word_path.path_iter(0.1),
+        Err(PathError::EmptyWord)
+    ));
+}
+
+#[test]
+// Test case B21
+fn test_key_layout() {
+    let centers = get_default_buttons_centers();
+
+    // A layout from the centers knows all keys, but not their sizes
+    let key_layout = KeyLayout::from_centers("centers", &centers).unwrap();
+    assert!(key_layout.name() == "centers");
+    assert!(key_layout.keys().len() == 26);
+    assert!(key_layout.to_centers() == centers);
+    let key = key_layout.key_for_char('h').unwrap();
+    assert!(key.label == "h" && key.center == (0.6, 0.15) && key.size().is_none());
+    assert!(key_layout.key_for_char('ü').is_none());
+    let bounds = key_layout.bounds();
+    assert!(float_cmp(bounds.min_x, 0.05) && float_cmp(bounds.max_x, 0.95));
+    assert!(float_cmp(bounds.min_y, 0.05) && float_cmp(bounds.max_y, 0.25));
+
+    // The default layout knows the size of its keys
+    let key_layout = get_default_layout();
+    assert!(key_layout.to_centers() == centers);
+    let key = key_layout.key_for_char('q').unwrap();
+    let (width, height) = key.size().unwrap();
+    assert!(float_cmp(width, 0.1) && float_cmp(height, 0.1));
+    let bounds = key_layout.bounds();
+    assert!(float_cmp(bounds.min_x, 0.0) && float_cmp(bounds.max_x, 1.0));
+    assert!(float_cmp(bounds.min_y, 0.0) && float_cmp(bounds.max_y, 0.3));
+
+    // Invalid layouts are rejected
+    assert!(KeyLayout::new("empty", Vec::new()) == Err(LayoutError::NoKeys));
+    let mut key = Key::new("a", (0.1, 0.1));
+    key.chars.push('a');
+    assert!(
+        KeyLayout::new("duplicate", vec![key])
+            == Err(LayoutError::DuplicateCharacter { character: 'a' })
+    );
+    let keys = vec![Key::new("a", (f64::NAN, 0.1))];
+    assert!(matches!(
+        KeyLayout::new("nan", keys),
+        Err(LayoutError::NonFiniteGeometry { .. })
+    ));
+}
+
+#[test]
+// Test case B22
+fn test_key_lookup() {
+    // A key model of a keyboard that computes the positions of its keys
+    struct GridKeyboard {
+        rows: Vec<&'static str>,
+    }
+    impl KeyLookup for GridKeyboard {
+        fn key_position(&self, character: char) -> Option<Point> {
+            self.rows.iter().enumerate().find_map(|(row, keys)| {
+                keys.chars()
+                    .position(|key| key == character)
+                    .map(|column| ((column as f64 + 0.5) / 10.0, (row as f64 + 0.5) / 10.0))
+            })
+        }
+        fn all_keys(&self) -> Vec<(String, Point)> {
+            self.rows
+                .iter()
+                .flat_map(|keys| keys.chars())
+                .map(|key| (key.to_string(), self.key_position(key).unwrap()))
+                .collect()
+        }
+    }
+
+    let centers = get_default_buttons_centers();
+    let key_layout = get_default_layout();
+    let grid_keyboard = GridKeyboard {
+        rows: vec!["abc", "def"],
+    };
+    assert!(grid_keyboard.all_keys().len() == 6);
+    assert!(key_layout.all_keys().len() == centers.all_keys().len());
+
+    let path = WordPath::new(&centers, "hello").get_path(0.1);
+    assert!(WordPath::new(&key_layout, "hello").get_path(0.1) == path);
+    let lookup: &dyn KeyLookup = &key_layout;
+    assert!(WordPath::new(lookup, "hello").get_path(0.1) == path);
+
+    let word_path = WordPath::new(&grid_keyboard, "bad");
+    assert!(word_path.get_waypoints() == Ok(vec![(0.15, 0.05), (0.05, 0.05), (0.05, 0.15)]));
+    assert!(word_path.get_first_last_points() == (Some((0.15, 0.05)), Some((0.05, 0.15))));
+}
+
+#[test]
+// Test case B23
+fn test_shared_word_path() {
+    fn assert_send_sync<T: Send + Sync + Clone + 'static>() {}
+    assert_send_sync::<SharedWordPath>();
+
+    let key_layout = Arc::new(get_default_layout());
+
+    // Build the templates in a background thread and cache them
+    let words = ["hello", "family", "spaceship"];
+    let thread_layout = Arc::clone(&key_layout);
+    let cache: HashMap<String, SharedWordPath> = std::thread::spawn(move || {
+        words
+            .iter()
+            .map(|&word| {
+                (
+                    word.to_string(),
+                    WordPath::new(Arc::clone(&thread_layout), word),
+                )
+            })
+            .collect()
+    })
+    .join()
+    .unwrap();
+
+    for word in words {
+        let cached = cache[word].clone();
+        let borrowed = WordPath::new(&*key_layout, word);
+        assert!(cached.get_path(0.1) == borrowed.get_path(0.1));
+    }
+    // The layout is shared by all templates
+    assert!(Arc::strong_count(&key_layout) == 1 + words.len());
+}
+
+#[test]
+// Test case B24
+fn test_key_shapes() {
+    // The centroid of an L-shaped key is not the average of its corners
+    let enter = KeyShape::Polygon(vec![
+        (0.0, 0.0),
+        (2.0, 0.0),
+        (2.0, 2.0),
+        (1.0, 2.0),
+        (1.0, 1.0),
+        (0.0, 1.0),
+    ]);
+    let (x, y) = enter.centroid();
+    assert!(float_cmp(x, 7.0 / 6.0) && float_cmp(y, 5.0 / 6.0));
+    assert!(float_cmp(enter.area(), 3.0));
+
+    // The orientation of a polygon does not matter
+    let trapezoid = KeyShape::Polygon(vec![(0.0, 0.0), (1.0, 1.0), (3.0, 1.0), (4.0, 0.0)]);
+    let (x, y) = trapezoid.centroid();
+    assert!(float_cmp(x, 2.0) && float_cmp(y, 4.0 / 9.0));
+    assert!(float_cmp(trapezoid.area(), 3.0));
+
+    let rounded = KeyShape::RoundedRect {
+        x: 0.1,
+        y: 0.2,
+        width: 0.1,
+        height: 0.05,
+        radius: 0.01,
+    };
+    let (x, y) = rounded.centroid();
+    assert!(float_cmp(x, 0.15) && float_cmp(y, 0.225));
+    assert!(rounded.area() < 0.1 * 0.05);
+
+    // A layout from shapes has the centroids as centers
+    let key_layout = KeyLayout::from_shapes(
+        "split",
+        vec![
+            ("\n".to_string(), enter),
+            ("t".to_string(), trapezoid),
+            ("r".to_string(), rounded),
+        ],
+    )
+    .unwrap();
+    let key = key_layout.key_for_char('\n').unwrap();
+    assert!(float_cmp(key.center.0, 7.0 / 6.0) && float_cmp(key.center.1, 5.0 / 6.0));
+    let (width, height) = key.size().unwrap();
+    assert!(float_cmp(width, 2.0) && float_cmp(height, 2.0));
+    let bounds = key_layout.bounds();
+    assert!(float_cmp(bounds.min_x, 0.0) && float_cmp(bounds.max_x, 4.0));
+    assert!(float_cmp(bounds.min_y, 0.0) && float_cmp(bounds.max_y, 2.0));
+
+    // Shapes that can't be pressed are rejected
+    let too_round = KeyShape::RoundedRect {
+        x: 0.0,
+        y: 0.0,
+        width: 0.1,
+        height: 0.1,
+        radius: 0.06,
+    };
+    for shape in [too_round, KeyShape::Polygon(Vec::new())] {
+        assert!(
+            KeyLayout::from_shapes("invalid", vec![("a".to_string(), shape)])
+                == Err(LayoutError::InvalidShape {
+                    label: "a".to_string()
+                })
+        );
+    }
+}
+
+#[test]
+// Test case B25
+fn test_export_layout() {
+    let centers = get_button_centers();
+    assert!(centers.len() == 26);
+    let key_layout = KeyLayout::from_centers("buttons", &centers).unwrap();
+    assert!(key_layout.to_centers() == centers);
+
+    // The Rust source reproduces the default centers
+    let source = get_default_layout().to_rust_source("get_default_buttons_centers");
+    assert!(source.starts_with(
+        "pub fn get_default_buttons_centers() -> HashMap<String, (f64, f64)> {\n    let mut buttons = HashMap::new();\n    buttons.insert(\"a\".to_string(), (0.1, 0.15));\n"
+    ));
+    assert!(
+        source.contains("    buttons.insert(\"z\".to_string(), (0.15, 0.25));\n    buttons\n}\n")
+    );
+    assert!(source.lines().count() == 26 + 4);
+
+    // Labels are escaped and shapes are written
+    let key_layout = KeyLayout::new(
+        "special \"keys\"",
+        vec![
+            Key::new("\"", (0.5, 0.5)),
+            Key::from_shape(
+                "enter",
+                KeyShape::Polygon(vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]),
+            ),
+            Key::from_shape(
+                "ä",
+                KeyShape::Rect {
+                    x: 1.0,
+                    y: 0.0,
+                    width: 0.5,
+                    height: 1e-7,
+                },
+            ),
+        ],
+    )
+    .unwrap();
+    let json = key_layout.to_json();
+    assert!(json.contains("\"name\": \"special \\\"keys\\\"\""));
+    assert!(
+        json.contains("{ \"label\": \"\\\"\", \"chars\": [\"\\\"\"], \"center\": [0.5, 0.5] },")
+    );
+    assert!(json.contains(
+        "\"shape\": { \"type\": \"polygon\", \"corners\": [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]] }"
+    ));
+    assert!(json.contains(
+        "\"bounds\": { \"min_x\": 0.0, \"min_y\": 0.0, \"max_x\": 1.5, \"max_y\": 1.0 }"
+    ));
+    let toml = key_layout.to_toml();
+    assert!(
+        toml.starts_with("version = 1\nname = \"special \\\"keys\\\"\"\nunits = \"normalized\"\n")
+    );
+    assert!(toml.contains(
+        "[[keys]]\nlabel = \"ä\"\nchars = [\"ä\"]\ncenter = [1.25, 5e-8]\nsize = [0.5, 1e-7]\n"
+    ));
+}
+
+#[test]
+// Test case B26
+fn test_squeekboard_layout() {
+    let source = r#"---
+# Simplified version of the US layout
+outlines:
+    default: { width: 10, height: 10 }
+    altline: { width: 15, height: 10 }
+    spaceline: { width: 40, height: 10 }
+    special: { bounds: { x: 0, y: 0, width: 10, height: 8 } }
+
+views:
+    base:
+        - "q w e r t y u i o p"
+        - "a s d f g h j k l"
+        - "Shift_L   z x c v b n m  BackSpace"
+        - "show_numbers preferences         space        period Return"
+    numbers:
+        - "1 2 3 4 5 6 7 8 9 0"
+        - "' # BackSpace"
+
+buttons:
+    Shift_L:
+        action:
+            locking:
+                lock_view: "upper"
+                unlock_view: "base"
+        outline: "altline"
+        icon: "key-shift"
+    BackSpace:
+        outline: "altline"
+        icon: "edit-clear-symbolic"
+        action: erase
+    preferences:
+        action: "show_prefs"
+        outline: "special"
+        icon: "keyboard-mode-symbolic"
+    show_numbers:
+        action:
+            set_view: "numbers"
+        outline: "altline"
+        label: "123"
+    space:
+        outline: "spaceline"
+        text: " "
+    Return:
+        outline: "altline"
+        icon: "key-enter"
+        keysym: "Return"
+    period:
+        outline: 'altline' # Single quotes work as well
+        text: "."
+    "'":
+        label: 'apostrophe ''quoted'''
+"#;
+    let key_layout = KeyLayout::from_squeekboard_yaml(source, "base").unwrap();
+    assert!(key_layout.name() == "base");
+    assert!(key_layout.keys().len() == 10 + 9 + 9 + 5);
+    let bounds = key_layout.bounds();
+    assert!(float_cmp(bounds.min_x, 0.0) && float_cmp(bounds.min_y, 0.0));
+    assert!(float_cmp(bounds.max_x, 1.0) && float_cmp(bounds.max_y, 0.4));
+
+    // The first two rows are the same as in the default layout
+    let default_layout = get_default_layout();
+    for character in "qwertyuiopasdfghjkl".chars() {
+        let (x, y) = key_layout.key_position(character).unwrap();
+        let (default_x, default_y) = default_layout.key_position(character).unwrap();
+        assert!(float_cmp(x, default_x) && float_cmp(y, default_y));
+    }
+    // The third row is shifted by the wider shift key
+    let (x, y) = key_layout.key_position('z').unwrap();
+    assert!(float_cmp(x, 0.2) && float_cmp(y, 0.25));
+
+    // Buttons produce their text and are labeled with their label or name
+    let space = key_layout.key_for_char(' ').unwrap();
+    assert!(space.label == "space");
+    let (x, y) = space.center;
+    assert!(float_cmp(x, 0.475) && float_cmp(y, 0.35));
+    assert!(key_layout.key_for_char('.').unwrap().label == "period");
+    let show_numbers = &key_layout.keys()[28];
+    assert!(show_numbers.label == "123" && show_numbers.chars.is_empty());
+    // Lower buttons are centered in their row
+    let preferences = &key_layout.keys()[29];
+    let (width, height) = preferences.size().unwrap();
+    assert!(float_cmp(width, 0.1) && float_cmp(height, 0.08));
+    assert!(float_cmp(preferences.center.1, 0.35));
+
+    // Words can be swiped on the layout
+    let word_path = WordPath::new(&key_layout, "hello world");
+    assert!(word_path.get_path(0.01).is_ok());
+
+    let key_layout = KeyLayout::from_squeekboard_yaml(source, "numbers").unwrap();
+    assert!(key_layout.key_for_char('\'').unwrap().label == "apostrophe 'quoted'");
+    assert!(key_layout.key_for_char('#').is_some());
+
+    // Errors
+    assert!(
+        KeyLayout::from_squeekboard_yaml(source, "upper")
+            == Err(ImportError::MissingField {
+                field: "views.upper".to_string()
+            })
+    );
+    let source = "outlines:\n  default: { width: 1, height: 1 }\nviews:\n  base:\n    - \"a b\"\n    - \"Tab\"\nbuttons:\n  Tab: { outline: wide }\n";
+    assert!(
+        KeyLayout::from_squeekboard_yaml(source, "base")
+            == Err(ImportError::MissingField {
+                field: "outlines.wide".to_string()
+            })
+    );
+    let source = source.replace("default: { width: 1,", "default: { width: -1,");
+    assert!(
+        KeyLayout::from_squeekboard_yaml(&source, "base")
+            == Err(ImportError::InvalidValue {
+                field: "outlines.default.width".to_string(),
+                value: "-1".to_string()
+            })
+    );
+    let source = "views:\n  base:\n    - \"a b\n";
+    assert!(matches!(
+        KeyLayout::from_squeekboard_yaml(source, "base"),
+        Err(ImportError::Syntax { line: 3, .. })
+    ));
+    let source = "outlines:\n  default: { width: 1, height: 1 }\nviews:\n  base: [\"a b a\"]\n";
+    let key_layout = KeyLayout::from_squeekboard_yaml(source, "base").unwrap();
+    let positions = key_layout.key_positions('a');
+    assert!(positions.len() == 2 && float_cmp(positions[0].0, 1.0 / 6.0));
+    assert!(float_cmp(positions[1].0, 5.0 / 6.0) && float_cmp(positions[1].1, 1.0 / 6.0));
+
+    // Deeply nested files are rejected instead of overflowing the stack
+    let nested_flow = format!("a: {}", "[".repeat(200_000));
+    assert!(matches!(
+        KeyLayout::from_squeekboard_yaml(&nested_flow, "base"),
+        Err(ImportError::Syntax { line: 1, .. })
+    ));
+    let nested_block = format!("{}a", "- ".repeat(200_000));
+    assert!(matches!(
+        KeyLayout::from_squeekboard_yaml(&nested_block, "base"),
+        Err(ImportError::Syntax { line: 1, .. })
+    ));
+    let nested_block: String = (0..1000)
+        .map(|depth| format!("{}a{}:\n", " ".repeat(depth), depth))
+        .collect();
+    assert!(matches!(
+        KeyLayout::from_squeekboard_yaml(&nested_block, "base"),
+        Err(ImportError::Syntax { line: 129, .. })
+    ));
+}
+
+#[test]
+// Test case B27
+fn test_layout_file_format() {
+    // Layouts are written and read without losing information
+    let default_layout = get_default_layout();
+    assert!(KeyLayout::from_json(&default_layout.to_json()).unwrap() == default_layout);
+    assert!(KeyLayout::from_toml(&default_layout.to_toml()).unwrap() == default_layout);
+    // The corners of the rows are not always exactly half a key away from the centers
+    for name in builtin_layout_names() {
+        let key_layout = get_builtin_layout(name).unwrap();
+        assert!(KeyLayout::from_json(&key_layout.to_json()).unwrap() == key_layout);
+        assert!(KeyLayout::from_toml(&key_layout.to_toml()).unwrap() == key_layout);
+    }
+    let key_layout = KeyLayout::from_row_string(
+        "rows",
+        "q w e / a s d / z x c",
+        &RowLayoutOptions::default(),
+    )
+    .unwrap();
+    assert!(key_layout.to_json().contains("\"shape\""));
+    assert!(KeyLayout::from_json(&key_layout.to_json()).unwrap() == key_layout);
+    assert!(KeyLayout::from_toml(&key_layout.to_toml()).unwrap() == key_layout);
+    let key_layout = KeyLayout::new(
+        "special\t\"keys\"",
+        vec![
+            Key::new("\\", (0.5, 0.5)),
+            Key::new("enter", (0.6, 0.4)).with_shape(KeyShape::Polygon(vec![
+                (0.0, 0.0),
+                (1.0, 0.0),
+                (1.0, 1.0),
+            ])),
+            Key::from_shape(
+                "ä",
+                KeyShape::RoundedRect {
+                    x: 1.0,
+                    y: 0.0,
+                    width: 0.5,
+                    height: 1.0 / 3.0,
+                    radius: 0.1,
+                },
+            ),
+            Key {
+                label: "shift".to_string(),
+                chars: Vec::new(),
+                center: (-1e-300, 1e300),
+                shape: None,
+            },
+        ],
+    )
+    .unwrap()
+    .with_units(Units::Millimetres);
+    assert!(KeyLayout::from_json(&key_layout.to_json()).unwrap() == key_layout);
+    assert!(KeyLayout::from_toml(&key_layout.to_toml()).unwrap() == key_layout);
+
+    // Hand-written files
+    let json = r#"{
+        "version": 1, "name": "example", "units": "pixels",
+        "bounds": { "min_x": -10, "min_y": 0, "max_x": 300, "max_y": 100 },
+        "keys": [
+            { "label": "a", "center": [50, 50], "size": [100, 100], "comment": "ignored" },
+            { "label": "\u00df", "center": [150, 50] },
+            { "label": "enter", "chars": ["\n", "\ud83d\ude00"],
+              "shape": { "type": "polygon", "corners": [[200, 0], [300, 0], [300, 90]] } }
+        ]
+    }"#;
+    let toml = r#"
+        # Same layout as the JSON file
+        version = 1
+        name = 'example'
+        units = "pixels"
+        bounds.min_x = -10
+        bounds.min_y = 0
+        bounds.max_x = 3_00
+        bounds.max_y = 1e2
+
+        [[keys]]
+        label = "a"
+        center = [50, 50] # The key is a square
+        size = [100.0, +100.0]
+
+        [[keys]]
+        label = "\u00DF"
+        center = [150, 50]
+
+        [[keys]]
+        label = "enter"
+        chars = ["\n", "\U0001F600"]
+        [keys.shape]
+        type = "polygon"
+        corners = [
+            [200, 0],
+            [300, 0],
+            [300, 90], # Trailing commas are allowed
+        ]
+    "#;
+    let key_layout = KeyLayout::from_json(json).unwrap();
+    assert!(KeyLayout::from_toml(toml).unwrap() == key_layout);
+    assert!(key_layout.name() == "example" && key_layout.units() == Units::Pixels);
+    assert!(
+        key_layout.bounds()
+            == Bounds {
+                min_x: -10.0,
+                min_y: 0.0,
+                max_x: 300.0,
+                max_y: 100.0
+            }
+    );
+    assert!(key_layout.keys()[0].size() == Some((100.0, 100.0)));
+    assert!(key_layout.key_position('ß') == Some((150.0, 50.0)));
+    assert!(key_layout.key_position('😀') == Some((800.0 / 3.0, 30.0)));
+    assert!(key_layout.keys()[2].chars == vec!['\n', '😀']);
+
+    // Errors
+    let invalid_json = |from: &str, to: &str| KeyLayout::from_json(&json.replace(from, to));
+    assert!(
+        invalid_json("\"version\": 1", "\"version\": 2")
+            == Err(ImportError::InvalidValue {
+                field: "version".to_string(),
+                value: "2".to_string()
+            })
+    );
+    assert!(
+        invalid_json("\"name\": \"example\",", "")
+            == Err(ImportError::MissingField {
+                field: "name".to_string()
+            })
+    );
+    assert!(
+        invalid_json("\"units\": \"pixels\"", "\"units\": \"inches\"")
+            == Err(ImportError::InvalidValue {
+                field: "units".to_string(),
+                value: "inches".to_string()
+            })
+    );
+    assert!(
+        invalid_json("\"center\": [150, 50]", "\"center\": [150]")
+            == Err(ImportError::InvalidValue {
+                field: "keys[1].center".to_string(),
+                value: "an array".to_string()
+            })
+    );
+    assert!(
+        invalid_json("[\"\\n\", ", "[\"ab\", ")
+            == Err(ImportError::InvalidValue {
+                field: "keys[2].chars[0]".to_string(),
+                value: "ab".to_string()
+            })
+    );
+    assert!(
+        invalid_json("\"type\": \"polygon\"", "\"type\": \"circle\"")
+            == Err(ImportError::InvalidValue {
+                field: "keys[2].shape.type".to_string(),
+                value: "circle".to_string()
+            })
+    );
+    assert!(
+        invalid_json("\"enter\", ", "\"enter\", \"size\": [1, 1], ")
+            == Err(ImportError::InvalidValue {
+                field: "keys[2].size".to_string(),
+                value: "a size and a shape".to_string()
+            })
+    );
+    assert!(
+        invalid_json("\"\\ud83d\\ude00\"", "\"\\n\"")
+            == Err(ImportError::Layout(LayoutError::DuplicateCharacter {
+                character: '\n'
+            }))
+    );
+    assert!(matches!(
+        invalid_json("[150, 50]", "[150, 050]"),
+        Err(ImportError::Syntax { line: 6, .. })
+    ));
+    assert!(matches!(
+        KeyLayout::from_toml(&toml.replace("[50, 50]", "[50, 50] 50")),
+        Err(ImportError::Syntax { line: 13, .. })
+    ));
+    assert!(matches!(
+        KeyLayout::from_toml(&toml.replace("label = \"a\"", "label = \"a\"\nlabel = \"b\"")),
+        Err(ImportError::Syntax { line: 13, .. })
+    ));
+
+    // Deeply nested files are rejected instead of overflowing the stack
+    let nested_json = "[".repeat(200_000);
+    assert!(matches!(
+        KeyLayout::from_json(&nested_json),
+        Err(ImportError::Syntax { line: 1, .. })
+    ));
+    let nested_json = format!("{}{}", "[".repeat(128), "]".repeat(128));
+    assert!(!matches!(
+        KeyLayout::from_json(&nested_json),
+        Err(ImportError::Syntax { .. })
+    ));
+    let nested_toml = format!("a = {}", "[".repeat(200_000));
+    assert!(matches!(
+        KeyLayout::from_toml(&nested_toml),
+        Err(ImportError::Syntax { line: 1, .. })
+    ));
+    let nested_toml = format!("a = {}", "{ b = ".repeat(200_000));
+    assert!(matches!(
+        KeyLayout::from_toml(&nested_toml),
+        Err(ImportError::Syntax { line: 1, .. })
+    ));
+    let dotted_toml = format!("a{} = 1", ".a".repeat(200_000));
+    assert!(matches!(
+        KeyLayout::from_toml(&dotted_toml),
+        Err(ImportError::Syntax { line: 1, .. })
+    ));
+}
+
+#[test]
+// Test case B28
+fn test_aosp_layout() {
+    let rows_qwerty = r#"<?xml version="1.0" encoding="utf-8"?>
+<!-- Layout in the style of LatinIME -->
+<merge xmlns:latin="http://schemas.android.com/apk/res-auto">
+    <key-style latin:styleName="baseKeyStyle" latin:keyWidth="10%p" />
+    <key-style latin:styleName="shiftKeyStyle" latin:parentStyle="baseKeyStyle"
+        latin:keySpec="!icon/shift_key|!code/key_shift" />
+    <Row latin:keyWidth="10%p">
+        <include latin:keyboardLayout="@xml/rowkeys_qwerty1" />
+    </Row>
+    <Row latin:keyWidth="10%p">
+        <include latin:keyboardLayout="@xml/rowkeys_qwerty2" latin:keyXPos="5%p" />
+    </Row>
+    <Row latin:keyWidth="20%p">
+        <Key latin:keyStyle="shiftKeyStyle" />
+        <include latin:keyboardLayout="@xml/rowkeys_qwerty3" latin:keyWidth="10%p" />
+        <Key latin:keySpec="!icon/delete_key|!code/key_delete" latin:keyWidth="fillRight" />
+    </Row>
+    <Row latin:keyWidth="10%p">
+        <switch>
+            <case latin:languageSwitchKeyEnabled="true">
+                <Key latin:keySpec="!icon/language_switch_key|!code/key_language_switch" />
+            </case>
+            <default>
+                <Spacer latin:keyWidth="20%p" />
+            </default>
+        </switch>
+        <Key latin:keySpec="&amp;" />
+        <Key latin:keySpec="!icon/space_key|!code/key_space" latin:keyWidth="50%p" />
+        <Key latin:keySpec="\|" latin:keyXPos="-10%p" />
+    </Row>
+</merge>
+"#;
+    let mut includes = HashMap::new();
+    for (idx, row) in ["qwertyuiop", "asdfghjkl", "zxcvbnm"].iter().enumerate() {
+        let keys: String = row
+            .chars()
+            .map(|character| format!("    <Key latin:keySpec=\"{}\" />\n", character))
+            .collect();
+        includes.insert(
+            format!("rowkeys_qwerty{}", idx + 1),
+            format!(
+                "<merge xmlns:latin=\"http://schemas.android.com/apk/res-auto\">\n{}</merge>\n",
+                keys
+            ),
+        );
+    }
+    let key_layout =
+        KeyLayout::from_aosp_xml_with_includes("qwerty", rows_qwerty, &includes).unwrap();
+    assert!(key_layout.name() == "qwerty");
+    assert!(key_layout.keys().len() == 10 + 9 + 9 + 3);
+
+    // The letters are at the same positions as in the default layout
+    let default_layout = get_default_layout();
+    for character in 'a'..='z' {
+        let (x, y) = key_layout.key_position(character).unwrap();
+        let (default_x, default_y) = default_layout.key_position(character).unwrap();
+        assert!(float_cmp(x, default_x) && float_cmp(y, default_y));
+        let (width, height) = key_layout.key_for_char(character).unwrap().size().unwrap();
+        assert!(float_cmp(width, 0.1) && float_cmp(height, 0.1));
+    }
+    let shift = &key_layout.keys()[19];
+    assert!(shift.label == "shift_key" && shift.chars.is_empty());
+    assert!(float_cmp(shift.center.0, 0.05));
+    let delete = &key_layout.keys()[27];
+    assert!(delete.label == "delete_key" && delete.chars.is_empty());
+    assert!(float_cmp(delete.center.0, 0.9) && float_cmp(delete.size().unwrap().0, 0.2));
+    let (x, y) = key_layout.key_position('&').unwrap();
+    assert!(float_cmp(x, 0.25) && float_cmp(y, 0.35));
+    let (x, y) = key_layout.key_position('|').unwrap();
+    assert!(float_cmp(x, 0.95) && float_cmp(y, 0.35));
+    assert!(key_layout.keys()[29].label == "space_key");
+
+    // Older layouts with key codes and gaps
+    let old_layout = r#"<Keyboard xmlns:android="http://schemas.android.com/apk/res/android"
+        android:keyWidth="25%p" android:horizontalGap="2%p" android:keyHeight="50dip">
+    <Row>
+        <Key android:codes="113" android:keyLabel="Q" android:keyEdgeFlags="left" />
+        <Key android:codes="-5" android:keyLabel="DEL" android:keyWidth="50%p" />
+    </Row>
+    <Row android:horizontalGap="0px">
+        <Key android:keyLabel="&#233;" />
+    </Row>
+</Keyboard>"#;
+    let key_layout = KeyLayout::from_aosp_xml("old", old_layout).unwrap();
+    let q = key_layout.key_for_char('q').unwrap();
+    assert!(q.label == "Q");
+    assert!(
+        q.shape
+            == Some(KeyShape::Rect {
+                x: 0.01,
+                y: 0.0,
+                width: 0.23,
+                height: 0.1
+            })
+    );
+    assert!(key_layout.keys()[1].label == "DEL" && key_layout.keys()[1].chars.is_empty());
+    let (x, y) = key_layout.key_position('é').unwrap();
+    assert!(float_cmp(x, 0.125) && float_cmp(y, 0.15));
+
+    // Errors
+    assert!(
+        KeyLayout::from_aosp_xml("qwerty", rows_qwerty)
+            == Err(ImportError::InvalidValue {
+                field: "include.keyboardLayout at line 8".to_string(),
+                value: "@xml/rowkeys_qwerty1".to_string()
+            })
+    );
+    assert!(
+        KeyLayout::from_aosp_xml("old", &old_layout.replace("50%p", "100px"))
+            == Err(ImportError::InvalidValue {
+                field: "Key.keyWidth at line 5".to_string(),
+                value: "100px".to_string()
+            })
+    );
+    let nested_rows = old_layout
+        .replace("    </Row>\n    <Row", "    <Row")
+        .replace("</Row>\n</Keyboard>", "</Row>\n</Row>\n</Keyboard>");
+    assert!(
+        KeyLayout::from_aosp_xml("old", &nested_rows)
+            == Err(ImportError::InvalidValue {
+                field: "Row at line 6".to_string(),
+                value: "inside of a Row".to_string()
+            })
+    );
+    assert!(matches!(
+        KeyLayout::from_aosp_xml("old", &old_layout.replace("</Keyboard>", "</Row>")),
+        Err(ImportError::Syntax { line: 10, .. })
+    ));
+    let nested_elements = "<a>".repeat(200_000);
+    assert!(matches!(
+        KeyLayout::from_aosp_xml("nested", &nested_elements),
+        Err(ImportError::Syntax { line: 1, .. })
+    ));
+
+    // Line numbers of large files are still right
+    let large_layout = format!(
+        "<Keyboard>\n<Row>\n{}<Key keySpec=\"b\" keyWidth=\"100px\" />\n</Row>\n</Keyboard>",
+        "<Key keySpec=\"a\" keyWidth=\"0.001%p\" />\n".repeat(20_000)
+    );
+    assert!(
+        KeyLayout::from_aosp_xml("large", &large_layout)
+            == Err(ImportError::InvalidValue {
+                field: "Key.keyWidth at line 20003".to_string(),
+                value: "100px".to_string()
+            })
+    );
+
+    // Files that include other files many times are rejected instead of expanding them forever
+    let fan_out = |level: usize| -> String {
+        let include = format!("<include keyboardLayout=\"@xml/level{}\" />", level + 1);
+        format!("<merge>{}</merge>", include.repeat(10))
+    };
+    let mut includes = HashMap::new();
+    for level in 1..6 {
+        includes.insert(format!("level{}", level), fan_out(level));
+    }
+    includes.insert("level6".to_string(), "<Key keySpec=\"a\" />".to_string());
+    let keyboard = |level: usize| -> String {
+        format!(
+            "<Keyboard keyWidth=\"1%p\">\n<Row>\n{}\n</Row>\n</Keyboard>",
+            fan_out(level)
+        )
+    };
+    assert!(matches!(
+        KeyLayout::from_aosp_xml_with_includes("fan_out", &keyboard(0), &includes),
+        Err(ImportError::InvalidValue { field, .. }) if field == "include.keyboardLayout at line 1"
+    ));
+    let key_layout =
+        KeyLayout::from_aosp_xml_with_includes("fan_out", &keyboard(4), &includes).unwrap();
+    assert!(key_layout.keys().len() == 100);
+}
+
+#[test]
+// Test case B29
+fn test_builtin_layouts() {
+    let names = builtin_layout_names();
+    for name in [
+        "qwerty", "qwertz", "azerty", "dvorak", "colemak", "workman", "nordic", "jcuken", "greek",
+    ] {
+        assert!(names.contains(&name));
+    }
+    assert!(get_builtin_layout("klingon").is_none());
+
+    // The QWERTY layout has the same keys as the default layout
+    let default_layout = get_default_layout();
+    let qwerty = get_builtin_layout("qwerty").unwrap();
+    assert!(qwerty.name() == "qwerty" && qwerty.keys().len() == 26);
+    for key in qwerty.keys() {
+        let default_key = default_layout.key_for_char(key.chars[0]).unwrap();
+        assert!(float_cmp(key.center.0, default_key.center.0));
+        assert!(float_cmp(key.center.1, default_key.center.1));
+        let (width, height) = key.size().unwrap();
+        assert!(float_cmp(width, 0.1) && float_cmp(height, 0.1));
+    }
+
+    // All layouts use keys of the same size and can be swiped on
+    for (name, word) in [
+        ("qwertz", "grüße"),
+        ("azerty", "bonjour"),
+        ("dvorak", "don't"),
+        ("colemak", "hello"),
+        ("workman", "hello"),
+        ("nordic", "hallå"),
+        ("jcuken", "Привет"),
+        ("greek", "γειά σας"),
+    ] {
+        let key_layout = get_builtin_layout(name).unwrap();
+        assert!(key_layout.name() == name);
+        for key in key_layout.keys() {
+            assert!(key.chars.len() == 1);
+            let (width, height) = key.size().unwrap();
+            assert!(float_cmp(width, 0.1) && float_cmp(height, 0.1));
+        }
+        let options = WordPathOptions {
+            missing_key_policy: MissingKeyPolicy::Skip,
+            diacritic_folding: Some(default_diacritic_folding()),
+            ..WordPathOptions::default()
+        };
+        let word_path = WordPath::with_options(&key_layout, word, &options);
+        assert!(word_path.get_path(0.01).is_ok());
+    }
+
+    // The positions follow the layouts
+    let azerty = get_builtin_layout("azerty").unwrap();
+    assert!(azerty.key_position('a') == default_layout.key_position('q'));
+    let dvorak = get_builtin_layout("dvorak").unwrap();
+    let (x, y) = dvorak.key_position('p').unwrap();
+    let (default_x, default_y) = default_layout.key_position('r').unwrap();
+    assert!(float_cmp(x, default_x) && float_cmp(y, default_y));
+    let jcuken = get_builtin_layout("jcuken").unwrap();
+    let (x, y) = jcuken.key_position('ъ').unwrap();
+    assert!(float_cmp(x, 1.15) && float_cmp(y, 0.05));
+    let nordic = get_builtin_layout("nordic").unwrap();
+    let (x, y) = nordic.key_position('ä').unwrap();
+    assert!(float_cmp(x, 1.1) && float_cmp(y, 0.15));
+}
+
+#[test]
+// Test case B30
+fn test_row_layout() {
+    // The default layout is reproduced from its rows
+    let default_layout = get_default_layout();
+    let key_layout = KeyLayout::from_row_string(
+        "rows",
+        "qwertyuiop / asdfghjkl / zxcvbnm",
+        &RowLayoutOptions::default(),
+    )
+    .unwrap();
+    assert!(key_layout.name() == "rows" && key_layout.keys().len() == 26);
+    assert!(key_layout.keys()[10].label == "a");
+    for key in key_layout.keys() {
+        let default_key = default_layout.key_for_char(key.chars[0]).unwrap();
+        assert!(float_cmp(key.center.0, default_key.center.0));
+        assert!(float_cmp(key.center.1, default_key.center.1));
+        match (&key.shape, &default_key.shape) {
+            (
+                Some(KeyShape::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                }),
+                Some(KeyShape::Rect {
+                    x: default_x,
+                    y: default_y,
+                    width: default_width,
+                    height: default_height,
+                }),
+            ) => {
+                assert!(float_cmp(*x, *default_x) && float_cmp(*y, *default_y));
+                assert!(float_cmp(*width, *default_width) && float_cmp(*height, *default_height));
+            }
+            _ => panic!("the keys are rectangles"),
+        }
+    }
+
+    // Sizes, gaps and offsets
+    let options = RowLayoutOptions {
+        key_width: 10.0,
+        key_height: 20.0,
+        horizontal_gap: 2.0,
+        vertical_gap: 4.0,
+        row_offsets: vec![5.0],
+    };
+    let key_layout = KeyLayout::from_rows("custom", &["a b c", "/.", "", "d"], &options).unwrap();
+    assert!(key_layout.keys().len() == 6);
+    assert!(
+        key_layout.key_for_char('c').unwrap().shape
+            == Some(KeyShape::Rect {
+                x: 29.0,
+                y: 0.0,
+                width: 10.0,
+                height: 20.0
+            })
+    );
+    assert!(key_layout.key_position('c') == Some((34.0, 10.0)));
+    assert!(key_layout.key_position('.') == Some((17.0, 34.0)));
+    assert!(key_layout.key_position('d') == Some((5.0, 82.0)));
+    assert!(
+        key_layout.bounds()
+            == Bounds {
+                min_x: 0.0,
+                min_y: 0.0,
+                max_x: 39.0,
+                max_y: 92.0
+            }
+    );
+
+    // Errors
+    assert!(
+        KeyLayout::from_row_string("empty", " / ", &RowLayoutOptions::default())
+            == Err(LayoutError::NoKeys)
+    );
+    let options = RowLayoutOptions {
+        key_width: -0.1,
+        ..RowLayoutOptions::default()
+    };
+    assert!(
+        KeyLayout::from_row_string("negative", "a", &options)
+            == Err(LayoutError::InvalidShape {
+                label: "a".to_string()
+            })
+    );
+}
+
+#[test]
+// Test case B31
+fn test_transform() {
+    let key_layout = get_default_layout();
+    let bounds = key_layout.bounds();
+    let mut widget = WidgetGeometry::new(1000.0, 300.0, 254.0);
+    widget.padding = Padding::uniform(10.0);
+
+    // The corners of the layout are mapped to the edges of the padding
+    let transform = Transform::normalized_to_pixels(&bounds, &widget).unwrap();
+    assert!(transform.source_units() == Units::Normalized);
+    assert!(transform.target_units() == Units::Pixels);
+    let (x, y) = transform.apply((bounds.min_x, bounds.min_y));
+    assert!(float_cmp(x, 10.0) && float_cmp(y, 10.0));
+    let (x, y) = transform.apply((bounds.max_x, bounds.max_y));
+    assert!(float_cmp(x, 990.0) && float_cmp(y, 290.0));
+
+    // Touch points are mapped back to the layout
+    let inverse = transform.inverse();
+    assert!(inverse.source_units() == Units::Pixels);
+    assert!(inverse.target_units() == Units::Normalized);
+    let point = (0.37, 0.21);
+    let (x, y) = inverse.apply(transform.apply(point));
+    assert!(float_cmp(x, point.0) && float_cmp(y, point.1));
+
+    // A flipped y axis keeps the top row at the top of the widget
+    widget.y_axis = YAxis::Up;
+    let flipped = Transform::normalized_to_pixels(&bounds, &widget).unwrap();
+    let (x, y) = flipped.apply((bounds.min_x, bounds.min_y));
+    assert!(float_cmp(x, 10.0) && float_cmp(y, 290.0));
+    let (x, y) = flipped.apply((bounds.max_x, bounds.max_y));
+    assert!(float_cmp(x, 990.0) && float_cmp(y, 10.0));
+    let (x, y) = flipped.inverse().apply(flipped.apply(point));
+    assert!(float_cmp(x, point.0) && float_cmp(y, point.1));
+
+    // 254 pixels per inch are 0.1 mm per pixel
+    let to_millimetres = Transform::pixels_to_millimetres(&widget).unwrap();
+    let (x, y) = to_millimetres.apply((100.0, 250.0));
+    assert!(float_cmp(x, 10.0) && float_cmp(y, 25.0));
+    widget.y_axis = YAxis::Down;
+    let composed = Transform::normalized_to_millimetres(&bounds, &widget).unwrap();
+    assert!(composed.source_units() == Units::Normalized);
+    assert!(composed.target_units() == Units::Millimetres);
+    let (x, y) = composed.apply((bounds.max_x, bounds.max_y));
+    assert!(float_cmp(x, 99.0) && float_cmp(y, 29.0));
+    let (x, y) = transform.then(&to_millimetres).apply(point);
+    let (expected_x, expected_y) = to_millimetres.apply(transform.apply(point));
+    assert!(float_cmp(x, expected_x) && float_cmp(y, expected_y));
+    let identity = Transform::identity(Units::Pixels);
+    assert!(identity.apply((3.0, 4.0)) == (3.0, 4.0));
+    assert!(transform.then(&transform.inverse()).target_units() == Units::Normalized);
+
+    // Paths keep their times
+    let timed_path = vec![
+        TimedPoint {
+            x: 0.0,
+            y: 0.0,
+            t: 0.0,
+        },
+        TimedPoint {
+            x: 0.5,
+            y: 0.1,
+            t: 0.25,
+        },
+    ];
+    let transformed = transform.apply_to_timed_path(&timed_path);
+    assert!(transformed.len() == 2);
+    assert!(float_cmp(transformed[1].x, 500.0) && float_cmp(transformed[1].y, 103.33333333333333));
+    assert!(transformed[1].t == 0.25);
+    assert!(transform.apply_to_path(&[point]) == vec![transform.apply(point)]);
+
+    // Layouts are transformed with their keys and bounds
+    let pixel_layout = flipped.apply_to_layout(&key_layout).unwrap();
+    assert!(pixel_layout.units() == Units::Pixels);
+    assert!(pixel_layout.name() == key_layout.name());
+    let pixel_bounds = pixel_layout.bounds();
+    assert!(float_cmp(pixel_bounds.min_x, 10.0) && float_cmp(pixel_bounds.max_x, 990.0));
+    assert!(float_cmp(pixel_bounds.min_y, 10.0) && float_cmp(pixel_bounds.max_y, 290.0));
+    let key = pixel_layout.key_for_char('q').unwrap();
+    assert!(float_cmp(key.center.0, 59.0) && float_cmp(key.center.1, 243.33333333333334));
+    // The folding table of the layout is kept
+    let folding: HashMap<char, String> = [('ß', "ss".to_string())].into_iter().collect();
+    let folding_layout = key_layout.clone().with_diacritic_folding(folding.clone());
+    let pixel_layout = flipped.apply_to_layout(&folding_layout).unwrap();
+    assert!(pixel_layout.diacritic_folding() == Some(&folding));
+    match key.shape {
+        Some(KeyShape::Rect {
+            x,
+            y,
+            width,
+            height,
+        }) => {
+            assert!(float_cmp(x, 10.0) && float_cmp(y, 196.66666666666669));
+            assert!(float_cmp(width, 98.0) && float_cmp(height, 93.33333333333333));
+        }
+        _ => panic!("the keys are rectangles"),
+    }
+    let (x, y) = flipped.inverse().apply(key.center);
+    let (expected_x, expected_y) = key_layout.key_position('q').unwrap();
+    assert!(float_cmp(x, expected_x) && float_cmp(y, expected_y));
+
+    // Errors
+    let widget = WidgetGeometry {
+        padding: Padding::uniform(200.0),
+        ..WidgetGeometry::new(1000.0, 300.0, 254.0)
+    };
+    assert!(
+        Transform::normalized_to_pixels(&bounds, &widget)
+            == Err(TransformError::InvalidWidgetSize {
+                width: 600.0,
+                height: -100.0
+            })
+    );
+    let widget = WidgetGeometry::new(1000.0, 300.0, 0.0);
+    assert!(Transform::pixels_to_millimetres(&widget) == Err(TransformError::InvalidDpi(0.0)));
+    assert!(
+        Transform::normalized_to_millimetres(&bounds, &widget)
+            == Err(TransformError::InvalidDpi(0.0))
+    );
+    let empty = Bounds {
+        min_x: 0.0,
+        min_y: 0.5,
+        max_x: 1.0,
+        max_y: 0.5,
+    };
+    assert!(Transform::normalized_to_pixels(&empty, &widget) == Err(TransformError::EmptyBounds));
+}
+
+#[test]
+// Test case B32
+fn test_multiple_key_instances() {
+    // A split layout with the letter 'a' on both halves
+    let keys = vec![
+        Key::new("a", (0.45, 0.0)),
+        Key::new("x", (0.5, 0.0)),
+        Key::new("c", (0.9, 0.0)),
+        Key::new("b", (1.0, 0.0)),
+        Key::new("a", (0.6, 0.0)),
+    ];
+    let key_layout = KeyLayout::new("split", keys).unwrap();
+    assert!(key_layout.keys_for_char('a').count() == 2);
+    assert!(key_layout.key_for_char('a').unwrap().center == (0.45, 0.0));
+    assert!(key_layout.key_position('a') == Some((0.45, 0.0)));
+    assert!(key_layout.key_positions('a') == vec![(0.45, 0.0), (0.6, 0.0)]);
+    assert!(key_layout.key_positions('b') == vec![(1.0, 0.0)]);
+    assert!(key_layout.key_positions('z').is_empty());
+    assert!(key_layout.key_count('a') == 2 && key_layout.key_count('b') == 1);
+    assert!(key_layout.key_count('z') == 0);
+    assert!(key_layout.to_centers()["a"] == (0.45, 0.0));
+
+    // The closest key to the previous letter is not always on the shortest path
+    let word_path = WordPath::new(&key_layout, "xab");
+    assert!(word_path.key_instances() == vec![(0, 0), (1, 1), (2, 0)]);
+    assert!(word_path.get_waypoints().unwrap() == vec![(0.5, 0.0), (0.6, 0.0), (1.0, 0.0)]);
+    assert!(float_cmp(word_path.get_total_length().unwrap(), 0.5));
+    let path = word_path.get_path(0.05).unwrap();
+    assert!(path.len() == 11);
+    assert!(path.iter().all(|point| point.0 >= 0.5));
+    assert!(word_path.get_first_last_points() == (Some((0.5, 0.0)), Some((1.0, 0.0))));
+
+    // The same instance is chosen for repeated letters and ties prefer the first key
+    let word_path = WordPath::new(&key_layout, "acA");
+    assert!(word_path.key_instances() == vec![(0, 1), (1, 0), (2, 1)]);
+    let word_path = WordPath::new(Arc::new(key_layout.clone()), "xac");
+    assert!(word_path.key_instances() == vec![(0, 0), (1, 1), (2, 0)]);
+    let keys = vec![
+        Key::new("a", (0.0, 0.0)),
+        Key::new("b", (0.5, 0.0)),
+        Key::new("a", (1.0, 0.0)),
+    ];
+    let symmetric_layout = KeyLayout::new("symmetric", keys).unwrap();
+    let word_path = WordPath::new(&symmetric_layout, "ab");
+    assert!(word_path.key_instances() == vec![(0, 0), (1, 0)]);
+
+    // Letters without a key split the word into parts that are chosen independently
+    let word_path = WordPath::new(&key_layout, "bazxa");
+    assert!(word_path.key_instances() == vec![(0, 0), (1, 1), (2, 0), (3, 0), (4, 0)]);
+    assert!(
+        word_path.get_path(0.1)
+            == Err(PathError::MissingKey {
+                character: 'z',
+                index: 2
+            })
+    );
+
+    // Lookups with a single key per letter never look up all positions
+    struct SingleKeyLookup(HashMap<String, Point>);
+    impl KeyLookup for SingleKeyLookup {
+        fn key_position(&self, character: char) -> Option<Point> {
+            self.0.key_position(character)
+        }
+
+        fn key_positions(&self, _character: char) -> Vec<Point> {
+            panic!("the positions are only needed for letters with several keys")
+        }
+
+        fn all_keys(&self) -> Vec<(String, Point)> {
+            self.0.all_keys()
+        }
+    }
+    let word_path = WordPath::new(SingleKeyLookup(get_default_buttons_centers()), "hello");
+    let expected_path = WordPath::new(get_default_buttons_centers(), "hello").get_path(0.01);
+    assert!(word_path.get_path(0.01) == expected_path);
+    assert!(word_path.key_instances() == vec![(0, 0), (1, 0), (2, 0), (4, 0)]);
+    assert!(word_path.key_layout().key_position('h') == Some((0.6, 0.15)));
+
+    // Lookups with a single key per letter use the first instance
+    let centers = get_default_buttons_centers();
+    assert!(centers.key_positions('q') == vec![(0.05, 0.05)]);
+    let word_path = WordPath::new(&centers, "hello");
+    assert!(word_path.key_instances() == vec![(0, 0), (1, 0), (2, 0), (4, 0)]);
+}
+
+#[test]
+// Test case B33
+fn test_hit_testing() {
+    // Rectangles
+    let key_layout = get_default_layout();
+    assert!(key_layout.key_containing((0.31, 0.16)).unwrap().label == "d");
+    assert!(key_layout.key_at((0.31, 0.16)).label == "d");
+    // Keys sharing a border are decided by their centers, ties prefer the first key
+    assert!(key_layout.key_at((0.36, 0.15)).label == "f");
+    assert!(key_layout.key_at((0.35, 0.15)).label == "d");
+    // Points outside of all keys fall back to the closest center
+    assert!(key_layout.key_containing((1.2, 0.05)).is_none());
+    assert!(key_layout.key_at((1.2, 0.05)).label == "p");
+
+    // The nearest keys are sorted by the distance to their centers
+    let nearest = key_layout.nearest_keys((0.31, 0.16), 3);
+    let labels: Vec<&str> = nearest.iter().map(|(key, _)| key.label.as_str()).collect();
+    assert!(labels == vec!["d", "f", "c"]);
+    assert!(float_cmp(nearest[0].1, f64::sqrt(0.0002)));
+    assert!(float_cmp(nearest[1].1, f64::sqrt(0.0082)));
+    assert!(float_cmp(nearest[2].1, f64::sqrt(0.0097)));
+    assert!(key_layout.nearest_keys((0.31, 0.16), 100).len() == 26);
+    assert!(key_layout.nearest_keys((0.31, 0.16), 0).is_empty());
+
+    // Keys without a known geometry only use their centers
+    let centers = get_default_buttons_centers();
+    let key_layout = KeyLayout::from_centers("centers", &centers).unwrap();
+    assert!(key_layout.key_containing((0.31, 0.16)).is_none());
+    assert!(key_layout.key_at((0.31, 0.16)).label == "d");
+    assert!(key_layout.key_containing((0.3, 0.15)).is_none());
+
+    // Any lookup can find the keys with the closest positions, e.g. the one of a word path
+    let nearest = centers.nearest_positions((0.31, 0.16), 3);
+    let labels: Vec<&str> = nearest.iter().map(|(label, _, _)| label.as_str()).collect();
+    assert!(labels == vec!["d", "f", "c"]);
+    assert!(nearest[0].1 == centers["d"]);
+    assert!(float_cmp(nearest[2].2, f64::sqrt(0.0097)));
+    assert!(centers.nearest_positions((0.31, 0.16), 0).is_empty());
+    let word_path = WordPath::new(&centers, "dad");
+    let nearest = word_path.key_layout().nearest_positions((0.07, 0.16), 1);
+    assert!(nearest[0].0 == "a");
+    // Keys with the same distance are sorted by their label
+    let mut lookup = HashMap::new();
+    lookup.insert("b".to_string(), (1.0, 0.0));
+    lookup.insert("a".to_string(), (-1.0, 0.0));
+    let nearest = lookup.nearest_positions((0.0, 0.0), 2);
+    assert!(nearest[0].0 == "a" && nearest[1].0 == "b");
+
+    // Rounded rectangles exclude their corners
+    let rounded = KeyShape::RoundedRect {
+        x: 0.0,
+        y: 0.0,
+        width: 1.0,
+        height: 1.0,
+        radius: 0.25,
+    };
+    assert!(!rounded.contains(&(0.02, 0.02)));
+    assert!(rounded.contains(&(0.1, 0.1)));
+    assert!(rounded.contains(&(0.5, 0.0)));
+    assert!(rounded.contains(&(0.5, 0.5)));
+    assert!(!rounded.contains(&(1.1, 0.5)));
+    assert!(!rounded.contains(&(0.98, 0.98)));
+
+    // Polygons, including concave ones
+    let triangle = KeyShape::Polygon(vec![(0.0, 0.0), (1.0, 0.0), (0.0, 1.0)]);
+    assert!(triangle.contains(&(0.2, 0.2)));
+    assert!(!triangle.contains(&(0.6, 0.6)));
+    assert!(triangle.contains(&(0.5, 0.5)));
+    assert!(triangle.contains(&(1.0, 0.0)));
+    assert!(!triangle.contains(&(-0.1, 0.5)));
+    let l_shape = KeyShape::Polygon(vec![
+        (0.0, 0.0),
+        (2.0, 0.0),
+        (2.0, 1.0),
+        (1.0, 1.0),
+        (1.0, 2.0),
+        (0.0, 2.0),
+    ]);
+    assert!(l_shape.contains(&(0.5, 1.5)));
+    assert!(l_shape.contains(&(1.5, 0.5)));
+    assert!(!l_shape.contains(&(1.5, 1.5)));
+    let key_layout = KeyLayout::from_shapes(
+        "overlapping",
+        vec![
+            ("l".to_string(), l_shape),
+            (
+                "o".to_string(),
+                KeyShape::Rect {
+                    x: 0.5,
+                    y: 0.5,
+                    width: 2.0,
+                    height: 2.0,
+                },
+            ),
+        ],
+    )
+    .unwrap();
+    assert!(key_layout.key_at((0.2, 0.2)).label == "l");
+    assert!(key_layout.key_at((1.5, 1.5)).label == "o");
+    assert!(key_layout.key_at((0.9, 0.9)).label == "l");
+    assert!(key_layout.key_at((1.9, 0.9)).label == "o");
+}
+
+#[test]
+// Test case B34
+fn test_metrics() {
+    let key_layout = get_default_layout();
+    let template = WordPath::new(&key_layout, "hello");
+    let options = MetricOptions::default();
+    let ideal_path = template.get_path_resampled(options.no_points).unwrap();
+
+    // The ideal path matches itself, even with a different sampling
+    // Resampling the resampled path again cuts its corners, so it is not a perfect match
+    let dense_path = template.get_path(0.001).unwrap();
+    for user_path in [&ideal_path, &dense_path] {
+        // The points of the resampled ideal path are about 0.0114 apart
+        assert!(dtw_distance(user_path, &template, &options).unwrap() < 0.003);
+        assert!(frechet_distance(user_path, &template, &options).unwrap() < 0.006);
+        assert!(proportional_shape_distance(user_path, &template, &options).unwrap() < 0.002);
+        assert!(shark2_shape_distance(user_path, &template, &options).unwrap() < 0.002);
+        assert!(shark2_location_distance(user_path, &template, &options).unwrap() == 0.0);
+    }
+
+    // A shifted path has the same shape, but is further away
+    let shift = |path: &[Point], x: f64, y: f64| -> Vec<Point> {
+        path.iter()
+            .map(|point| (point.0 + x, point.1 + y))
+            .collect()
+    };
+    let shifted_path = shift(&dense_path, 0.0, 0.01);
+    let distance = proportional_shape_distance(&shifted_path, &template, &options).unwrap();
+    assert!(float_cmp(distance, 0.01));
+    // Matching the points with the same index already results in a distance of 0.01
+    let shifted_ideal_path = shift(&ideal_path, 0.0, 0.01);
+    let distance = frechet_distance(&shifted_ideal_path, &template, &options).unwrap();
+    assert!(distance > 0.0 && distance <= 0.01 + 1e-9);
+    let distance = dtw_distance(&shifted_ideal_path, &template, &options).unwrap();
+    assert!(distance > 0.0 && distance <= 0.005 + 1e-9);
+    assert!(shark2_shape_distance(&shifted_path, &template, &options).unwrap() < 1e-9);
+    assert!(shark2_location_distance(&shifted_path, &template, &options).unwrap() == 0.0);
+    let far_path = shift(&dense_path, 0.0, 0.1);
+    let distance = shark2_location_distance(&far_path, &template, &options).unwrap();
+    assert!(float_cmp(distance, 0.1));
+    assert!(shark2_shape_distance(&far_path, &template, &options).unwrap() < 1e-9);
+
+    // The shape channel ignores the size of the path
+    let scaled_path: Vec<Point> = dense_path
+        .iter()
+        .map(|point| (point.0 * 2.0, point.1 * 2.0))
+        .collect();
+    assert!(shark2_shape_distance(&scaled_path, &template, &options).unwrap() < 1e-9);
+    assert!(proportional_shape_distance(&scaled_path, &template, &options).unwrap() > 0.1);
+
+    // A single point that is far off only changes the Fréchet distance a lot
+    let mut outlier_path = dense_path.clone();
+    let middle = outlier_path.len() / 2;
+    outlier_path[middle].1 += 1.0;
+    assert!(frechet_distance(&outlier_path, &template, &options).unwrap() > 0.9);
+    assert!(dtw_distance(&outlier_path, &template, &options).unwrap() < 0.01);
+
+    // Other words are further away than the word itself
+    let other_template = WordPath::new(&key_layout, "help");
+    let metrics = [
+        dtw_distance,
+        frechet_distance,
+        proportional_shape_distance,
+        shark2_shape_distance,
+        shark2_location_distance,
+    ];
+    for metric in metrics {
+        let distance = metric(&dense_path, &template, &options).unwrap();
+        let other_distance = metric(&dense_path, &other_template, &options).unwrap();
+        assert!(other_distance > distance);
+    }
+    let reversed_path: Vec<Point> = ideal_path.iter().rev().copied().collect();
+    assert!(frechet_distance(&reversed_path, &template, &options).unwrap() > 0.25);
+
+    // Words with a single key and paths with a single point
+    let template = WordPath::new(&key_layout, "a");
+    for metric in metrics {
+        assert!(metric(&[(0.1, 0.15)], &template, &options).unwrap() == 0.0);
+    }
+    let distance = frechet_distance(&[(0.1, 0.25)], &template, &options).unwrap();
+    assert!(float_cmp(distance, 0.1));
+
+    // Errors
+    for metric in metrics {
+        assert!(metric(&[], &template, &options) == Err(PathError::EmptyUserPath));
+        let options = MetricOptions {
+            no_points: 1,
+            ..MetricOptions::default()
+        };
+        assert!(
+            metric(&[(0.1, 0.15)], &template, &options) == Err(PathError::InvalidPointCount(1))
+        );
+        let empty_template = WordPath::new(&key_layout, "");
+        assert!(
+            metric(&[(0.1, 0.15)], &empty_template, &MetricOptions::default())
+                == Err(PathError::EmptyWord)
+        );
+        let missing_template = WordPath::new(&key_layout, "1");
+        assert!(
+            metric(&[(0.1, 0.15)], &missing_template, &MetricOptions::default())
+                == Err(PathError::MissingKey {
+                    character: '1',
+                    index: 0
+                })
+        );
+    }
+}
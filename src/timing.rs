@@ -0,0 +1,134 @@
+use crate::{dist, PathError, Point};
+
+/// A point of a path together with the time at which it is reached
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimedPoint {
+    pub x: f64,
+    pub y: f64,
+    /// Time in seconds since the start of the path
+    pub t: f64,
+}
+
+/// Motor model used to time a path
+///
+/// Every leg between two waypoints is drawn as a minimum-jerk movement (Flash & Hogan, 1985). The finger starts and
+/// stops at each waypoint and reaches its peak velocity in the middle of the leg, so the path slows down at corners.
+/// The duration of a leg grows with the square root of its length, so long legs are drawn faster than short ones:
+///
+/// `duration = leg_time_offset + leg_time_slope * sqrt(leg_length)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VelocityProfile {
+    /// Time in seconds every leg takes, regardless of its length
+    pub leg_time_offset: f64,
+    /// Time in seconds per square root of the leg length in layout units
+    pub leg_time_slope: f64,
+}
+
+impl Default for VelocityProfile {
+    /// Values that result in realistic swipes on the default layout, which is one unit wide
+    fn default() -> Self {
+        Self {
+            leg_time_offset: 0.08,
+            leg_time_slope: 0.3,
+        }
+    }
+}
+
+impl VelocityProfile {
+    /// Duration of a leg with the given length
+    pub fn leg_duration(&self, leg_length: f64) -> f64 {
+        self.leg_time_offset + self.leg_time_slope * leg_length.sqrt()
+    }
+
+    // Check that every leg takes a finite time that is not negative and that not every leg is drawn instantly
+    pub(crate) fn check(&self) -> Result<(), PathError> {
+        let is_valid = self.leg_time_offset.is_finite()
+            && self.leg_time_slope.is_finite()
+            && self.leg_time_offset >= 0.0
+            && self.leg_time_slope >= 0.0
+            && (self.leg_time_offset > 0.0 || self.leg_time_slope > 0.0);
+        if !is_valid {
+            return Err(PathError::InvalidVelocityProfile {
+                leg_time_offset: self.leg_time_offset,
+                leg_time_slope: self.leg_time_slope,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Most samples a timed path may consist of
+/// More samples are needed if the path takes very long compared to the sample interval
+pub const MAX_TIMED_SAMPLES: usize = 1_000_000;
+
+// Fraction of the leg that is covered after the fraction tau of the leg duration has passed
+fn min_jerk(tau: f64) -> f64 {
+    let tau = tau.clamp(0.0, 1.0);
+    tau * tau * tau * (10.0 - 15.0 * tau + 6.0 * tau * tau)
+}
+
+/// Sample the movement along the waypoints every sample_interval seconds
+/// The last waypoint is always part of the result, even if it is reached in-between two samples
+/// An error is returned if the profile is invalid, the movement would take no time or forever or it needs more than
+/// `MAX_TIMED_SAMPLES` samples
+pub(crate) fn sample_min_jerk(
+    waypoints: &[Point],
+    sample_interval: f64,
+    profile: &VelocityProfile,
+) -> Result<Vec<TimedPoint>, PathError> {
+    profile.check()?;
+    // Calculate when each leg starts
+    let mut leg_start_times = Vec::with_capacity(waypoints.len());
+    let mut total_time = 0.0;
+    leg_start_times.push(total_time);
+    for leg in waypoints.windows(2) {
+        total_time += profile.leg_duration(dist(&leg[0], &leg[1]));
+        leg_start_times.push(total_time);
+    }
+    if !total_time.is_finite() || (waypoints.len() > 1 && total_time <= 0.0) {
+        return Err(PathError::InvalidVelocityProfile {
+            leg_time_offset: profile.leg_time_offset,
+            leg_time_slope: profile.leg_time_slope,
+        });
+    }
+
+    // Samples in-between the waypoints, the last waypoint is added afterwards
+    let no_samples = if total_time > 0.0 {
+        (total_time / sample_interval).ceil().max(1.0)
+    } else {
+        0.0
+    };
+    if !no_samples.is_finite() || no_samples > MAX_TIMED_SAMPLES as f64 {
+        return Err(PathError::TooManySamples(no_samples));
+    }
+
+    let mut path = Vec::with_capacity(no_samples as usize + 1);
+    let mut leg = 0;
+    for sample in 0..no_samples as usize {
+        let t = sample as f64 * sample_interval;
+        // Rounding may result in a sample at the very end
+        if t >= total_time {
+            break;
+        }
+        // Find the leg the finger is on at time t
+        while leg_start_times[leg + 1] <= t {
+            leg += 1;
+        }
+        let (start, end) = (waypoints[leg], waypoints[leg + 1]);
+        let leg_duration = leg_start_times[leg + 1] - leg_start_times[leg];
+        let progress = min_jerk((t - leg_start_times[leg]) / leg_duration);
+        path.push(TimedPoint {
+            x: start.0 + (end.0 - start.0) * progress,
+            y: start.1 + (end.1 - start.1) * progress,
+            t,
+        });
+    }
+
+    let &(x, y) = waypoints.last().unwrap();
+    path.push(TimedPoint {
+        x,
+        y,
+        t: total_time,
+    });
+    Ok(path)
+}
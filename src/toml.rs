@@ -0,0 +1,385 @@
+use crate::error::MAX_NESTING_DEPTH;
+use crate::format::Value;
+use crate::ImportError;
+
+/// Parse a TOML document
+/// Tables, arrays of tables, dotted keys, inline tables, arrays spanning several lines and single-line strings are
+/// supported. Multi-line strings and dates are not.
+pub(crate) fn parse(source: &str) -> Result<Value, ImportError> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        pos: 0,
+        depth: 0,
+    };
+    let mut document = Value::Table(Vec::new());
+    // Path of the table that key/value pairs are added to
+    let mut current_table: Vec<String> = Vec::new();
+    loop {
+        parser.skip_whitespace_and_comments();
+        match parser.peek() {
+            None => return Ok(document),
+            Some('[') if parser.chars.get(parser.pos + 1) == Some(&'[') => {
+                parser.pos += 2;
+                let path = parser.parse_key(']')?;
+                parser.expect(']')?;
+                parser.expect(']')?;
+                push_table(&mut document, &path).map_err(|message| parser.error(&message))?;
+                current_table = path;
+            }
+            Some('[') => {
+                parser.pos += 1;
+                let path = parser.parse_key(']')?;
+                parser.expect(']')?;
+                table_at(&mut document, &path).map_err(|message| parser.error(&message))?;
+                current_table = path;
+            }
+            Some(_) => {
+                let key = parser.parse_key('=')?;
+                parser.expect('=')?;
+                let value = parser.parse_value()?;
+                let path = [current_table.as_slice(), key.as_slice()].concat();
+                insert(&mut document, &path, value).map_err(|message| parser.error(&message))?;
+            }
+        }
+        parser.expect_line_end()?;
+    }
+}
+
+// Get the entries of the table at the path, which is created if it doesn't exist
+// The last table of an array of tables is used
+fn table_at<'v>(
+    document: &'v mut Value,
+    path: &[String],
+) -> Result<&'v mut Vec<(String, Value)>, String> {
+    let mut table = match document {
+        Value::Table(entries) => entries,
+        _ => unreachable!("the document is a table"),
+    };
+    for key in path {
+        let idx = match table.iter().position(|(entry_key, _)| entry_key == key) {
+            Some(idx) => idx,
+            None => {
+                table.push((key.clone(), Value::Table(Vec::new())));
+                table.len() - 1
+            }
+        };
+        table = match &mut table[idx].1 {
+            Value::Table(entries) => entries,
+            Value::Array(items) => match items.last_mut() {
+                Some(Value::Table(entries)) => entries,
+                _ => return Err(format!("'{}' is not an array of tables", key)),
+            },
+            _ => return Err(format!("'{}' is not a table", key)),
+        };
+    }
+    Ok(table)
+}
+
+// Append a new table to the array of tables at the path
+fn push_table(document: &mut Value, path: &[String]) -> Result<(), String> {
+    let (key, parent_path) = path.split_last().expect("keys are not empty");
+    let parent = table_at(document, parent_path)?;
+    match parent.iter_mut().find(|(entry_key, _)| entry_key == key) {
+        Some((_, Value::Array(items))) => items.push(Value::Table(Vec::new())),
+        Some(_) => return Err(format!("'{}' is not an array of tables", key)),
+        None => parent.push((key.clone(), Value::Array(vec![Value::Table(Vec::new())]))),
+    }
+    Ok(())
+}
+
+// Insert a value at the path, which must not be defined yet
+fn insert(document: &mut Value, path: &[String], value: Value) -> Result<(), String> {
+    let (key, parent_path) = path.split_last().expect("keys are not empty");
+    let parent = table_at(document, parent_path)?;
+    if parent.iter().any(|(entry_key, _)| entry_key == key) {
+        return Err(format!("duplicate key '{}'", key));
+    }
+    parent.push((key.clone(), value));
+    Ok(())
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    // Number of arrays and inline tables the parser is in
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn error(&self, message: &str) -> ImportError {
+        ImportError::Syntax {
+            line: self.chars[..self.pos.min(self.chars.len())]
+                .iter()
+                .filter(|&&character| character == '\n')
+                .count()
+                + 1,
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some('#') {
+            while !matches!(self.peek(), None | Some('\n')) {
+                self.pos += 1;
+            }
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        loop {
+            self.skip_spaces();
+            self.skip_comment();
+            match self.peek() {
+                Some('\n' | '\r') => self.pos += 1,
+                _ => return,
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ImportError> {
+        self.skip_spaces();
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("expected '{}'", expected)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    // Only a comment may follow a key/value pair or a table header on the same line
+    fn expect_line_end(&mut self) -> Result<(), ImportError> {
+        self.skip_spaces();
+        self.skip_comment();
+        match self.peek() {
+            None | Some('\n') => Ok(()),
+            Some('\r') if self.chars.get(self.pos + 1) == Some(&'\n') => Ok(()),
+            _ => Err(self.error("expected the end of the line")),
+        }
+    }
+
+    // Parse a key that may consist of several dotted parts and ends before the terminator
+    fn parse_key(&mut self, terminator: char) -> Result<Vec<String>, ImportError> {
+        let mut path = Vec::new();
+        loop {
+            self.skip_spaces();
+            let part = match self.peek() {
+                Some('"') => self.parse_basic_string()?,
+                Some('\'') => self.parse_literal_string()?,
+                _ => {
+                    let start = self.pos;
+                    while matches!(
+                        self.peek(),
+                        Some('A'..='Z' | 'a'..='z' | '0'..='9' | '_' | '-')
+                    ) {
+                        self.pos += 1;
+                    }
+                    if start == self.pos {
+                        return Err(self.error("expected a key"));
+                    }
+                    self.chars[start..self.pos].iter().collect()
+                }
+            };
+            path.push(part);
+            if path.len() > MAX_NESTING_DEPTH {
+                return Err(self.error(&format!("keys have more than {} parts", MAX_NESTING_DEPTH)));
+            }
+            self.skip_spaces();
+            match self.peek() {
+                Some('.') => self.pos += 1,
+                Some(character) if character == terminator => return Ok(path),
+                _ => {
+                    return Err(self.error(&format!("expected '.' or '{}' after a key", terminator)))
+                }
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, ImportError> {
+        self.skip_spaces();
+        match self.peek() {
+            Some('"') => self.parse_basic_string().map(Value::String),
+            Some('\'') => self.parse_literal_string().map(Value::String),
+            Some(opening @ ('[' | '{')) => {
+                if self.depth >= MAX_NESTING_DEPTH {
+                    return Err(self.error(&format!(
+                        "arrays and inline tables are nested more than {} levels deep",
+                        MAX_NESTING_DEPTH
+                    )));
+                }
+                self.depth += 1;
+                let value = match opening {
+                    '[' => self.parse_array(),
+                    _ => self.parse_inline_table(),
+                };
+                self.depth -= 1;
+                value
+            }
+            Some(_) => self.parse_scalar(),
+            None => Err(self.error("expected a value")),
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Value, ImportError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace_and_comments();
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(Value::Array(items));
+            }
+            items.push(self.parse_value()?);
+            self.skip_whitespace_and_comments();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                _ => return Err(self.error("expected ',' or ']' in an array")),
+            }
+        }
+    }
+
+    fn parse_inline_table(&mut self) -> Result<Value, ImportError> {
+        self.pos += 1;
+        let mut table = Value::Table(Vec::new());
+        self.skip_spaces();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(table);
+        }
+        loop {
+            let key = self.parse_key('=')?;
+            self.expect('=')?;
+            let value = self.parse_value()?;
+            insert(&mut table, &key, value).map_err(|message| self.error(&message))?;
+            self.skip_spaces();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(table);
+                }
+                _ => return Err(self.error("expected ',' or '}' in an inline table")),
+            }
+        }
+    }
+
+    fn parse_literal_string(&mut self) -> Result<String, ImportError> {
+        if self.chars[self.pos..].starts_with(&['\'', '\'', '\'']) {
+            return Err(self.error("multi-line strings are not supported"));
+        }
+        self.pos += 1;
+        let start = self.pos;
+        while !matches!(self.peek(), None | Some('\'' | '\n')) {
+            self.pos += 1;
+        }
+        if self.peek() != Some('\'') {
+            return Err(self.error("unterminated string"));
+        }
+        self.pos += 1;
+        Ok(self.chars[start..self.pos - 1].iter().collect())
+    }
+
+    fn parse_basic_string(&mut self) -> Result<String, ImportError> {
+        if self.chars[self.pos..].starts_with(&['"', '"', '"']) {
+            return Err(self.error("multi-line strings are not supported"));
+        }
+        self.pos += 1;
+        let mut string = String::new();
+        loop {
+            let character = match self.peek() {
+                None | Some('\n') => return Err(self.error("unterminated string")),
+                Some(character) => character,
+            };
+            self.pos += 1;
+            match character {
+                '"' => return Ok(string),
+                '\\' => {
+                    let escape = self
+                        .peek()
+                        .ok_or_else(|| self.error("unterminated string"))?;
+                    self.pos += 1;
+                    let escaped = match escape {
+                        '"' | '\\' => escape,
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'u' => self.parse_code_point(4)?,
+                        'U' => self.parse_code_point(8)?,
+                        _ => {
+                            return Err(
+                                self.error(&format!("unknown escape sequence '\\{}'", escape))
+                            )
+                        }
+                    };
+                    string.push(escaped);
+                }
+                character => string.push(character),
+            }
+        }
+    }
+
+    fn parse_code_point(&mut self, no_digits: usize) -> Result<char, ImportError> {
+        let digits: String = self.chars.iter().skip(self.pos).take(no_digits).collect();
+        if digits.len() != no_digits {
+            return Err(self.error("incomplete escape sequence"));
+        }
+        let character = u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error(&format!("invalid code point '{}'", digits)))?;
+        self.pos += no_digits;
+        Ok(character)
+    }
+
+    // Parse a boolean or a number
+    fn parse_scalar(&mut self) -> Result<Value, ImportError> {
+        let start = self.pos;
+        while matches!(
+            self.peek(),
+            Some('A'..='Z' | 'a'..='z' | '0'..='9' | '_' | '-' | '+' | '.')
+        ) {
+            self.pos += 1;
+        }
+        let scalar: String = self.chars[start..self.pos].iter().collect();
+        let unsigned = scalar.trim_start_matches(['+', '-']);
+        let sign = if scalar.starts_with('-') { -1.0 } else { 1.0 };
+        let radix = match unsigned.get(..2) {
+            Some("0x") => Some(16),
+            Some("0o") => Some(8),
+            Some("0b") => Some(2),
+            _ => None,
+        };
+        let value = match (scalar.as_str(), unsigned, radix) {
+            ("true", _, _) => Some(Value::Bool(true)),
+            ("false", _, _) => Some(Value::Bool(false)),
+            (_, "inf", _) => Some(Value::Number(sign * f64::INFINITY)),
+            (_, "nan", _) => Some(Value::Number(f64::NAN)),
+            (_, _, Some(radix)) => i64::from_str_radix(&unsigned[2..].replace('_', ""), radix)
+                .ok()
+                .map(|value| Value::Number(value as f64)),
+            // Underscores are only allowed between digits
+            _ if scalar.contains("__") || scalar.starts_with('_') || scalar.ends_with('_') => None,
+            _ => scalar
+                .replace('_', "")
+                .parse::<f64>()
+                .ok()
+                .filter(|_| unsigned.starts_with(|character: char| character.is_ascii_digit()))
+                .map(Value::Number),
+        };
+        value.ok_or_else(|| {
+            self.pos = start;
+            self.error(&format!("invalid value '{}'", scalar))
+        })
+    }
+}
@@ -0,0 +1,297 @@
+use crate::{
+    Bounds, Key, KeyLayout, KeyShape, LayoutError, Point, TimedPoint, TransformError, Units,
+};
+
+// Millimetres per inch, used to convert pixels to millimetres with the resolution of the screen
+const MM_PER_INCH: f64 = 25.4;
+
+/// Direction of the y axis of a coordinate space
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum YAxis {
+    /// The origin is at the top and y grows downwards, like in the layouts and most UI toolkits
+    #[default]
+    Down,
+    /// The origin is at the bottom and y grows upwards
+    Up,
+}
+
+/// Space between the border of a widget and its keys in pixels
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Padding {
+    pub left: f64,
+    pub top: f64,
+    pub right: f64,
+    pub bottom: f64,
+}
+
+impl Padding {
+    /// Create a padding that is the same on all sides
+    pub fn uniform(padding: f64) -> Self {
+        Self {
+            left: padding,
+            top: padding,
+            right: padding,
+            bottom: padding,
+        }
+    }
+}
+
+/// Geometry of the widget that shows a layout on the screen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WidgetGeometry {
+    /// Width of the widget in pixels
+    pub width: f64,
+    /// Height of the widget in pixels
+    pub height: f64,
+    /// Space between the border of the widget and the keys
+    pub padding: Padding,
+    /// Resolution of the screen in pixels per inch
+    pub dpi: f64,
+    /// Direction of the y axis of the pixel coordinates
+    pub y_axis: YAxis,
+}
+
+impl WidgetGeometry {
+    /// Create the geometry of a widget without padding whose y axis points down
+    pub fn new(width: f64, height: f64, dpi: f64) -> Self {
+        Self {
+            width,
+            height,
+            padding: Padding::default(),
+            dpi,
+            y_axis: YAxis::Down,
+        }
+    }
+}
+
+/// Mapping between two coordinate spaces that scales and moves each axis
+///
+/// A transform maps the points of a path or the keys of a layout from its source space to its target space. The
+/// inverse maps points back, e.g. touch points in pixels to the space of the layout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    scale_x: f64,
+    scale_y: f64,
+    offset_x: f64,
+    offset_y: f64,
+    source_units: Units,
+    target_units: Units,
+}
+
+impl Transform {
+    /// Create a transform that does not change the points
+    pub fn identity(units: Units) -> Self {
+        Self {
+            scale_x: 1.0,
+            scale_y: 1.0,
+            offset_x: 0.0,
+            offset_y: 0.0,
+            source_units: units,
+            target_units: units,
+        }
+    }
+
+    /// Create a transform from normalized layout coordinates to the pixels of a widget
+    /// The bounds of the layout are stretched to fill the widget inside of its padding.
+    pub fn normalized_to_pixels(
+        layout_bounds: &Bounds,
+        widget: &WidgetGeometry,
+    ) -> Result<Self, TransformError> {
+        let content_width = widget.width - widget.padding.left - widget.padding.right;
+        let content_height = widget.height - widget.padding.top - widget.padding.bottom;
+        // Also rejects NaN
+        let is_valid = content_width > 0.0
+            && content_height > 0.0
+            && content_width.is_finite()
+            && content_height.is_finite();
+        if !is_valid {
+            return Err(TransformError::InvalidWidgetSize {
+                width: content_width,
+                height: content_height,
+            });
+        }
+        let scale_x = content_width / layout_bounds.width();
+        let scale_y = content_height / layout_bounds.height();
+        if !(scale_x.is_finite() && scale_y.is_finite() && scale_x > 0.0 && scale_y > 0.0) {
+            return Err(TransformError::EmptyBounds);
+        }
+
+        let offset_x = widget.padding.left - layout_bounds.min_x * scale_x;
+        let (scale_y, offset_y) = match widget.y_axis {
+            YAxis::Down => (scale_y, widget.padding.top - layout_bounds.min_y * scale_y),
+            // The top of the layout is at the top of the widget, which is the highest y coordinate
+            YAxis::Up => (
+                -scale_y,
+                widget.height - widget.padding.top + layout_bounds.min_y * scale_y,
+            ),
+        };
+        Ok(Self {
+            scale_x,
+            scale_y,
+            offset_x,
+            offset_y,
+            source_units: Units::Normalized,
+            target_units: Units::Pixels,
+        })
+    }
+
+    /// Create a transform from the pixels of a widget to millimetres on the screen
+    /// The origin and the direction of the y axis stay the same.
+    pub fn pixels_to_millimetres(widget: &WidgetGeometry) -> Result<Self, TransformError> {
+        if !(widget.dpi > 0.0 && widget.dpi.is_finite()) {
+            return Err(TransformError::InvalidDpi(widget.dpi));
+        }
+        let scale = MM_PER_INCH / widget.dpi;
+        Ok(Self {
+            scale_x: scale,
+            scale_y: scale,
+            offset_x: 0.0,
+            offset_y: 0.0,
+            source_units: Units::Pixels,
+            target_units: Units::Millimetres,
+        })
+    }
+
+    /// Create a transform from normalized layout coordinates to millimetres on the screen
+    pub fn normalized_to_millimetres(
+        layout_bounds: &Bounds,
+        widget: &WidgetGeometry,
+    ) -> Result<Self, TransformError> {
+        Ok(Self::normalized_to_pixels(layout_bounds, widget)?
+            .then(&Self::pixels_to_millimetres(widget)?))
+    }
+
+    /// Get the transform that maps the points back to the source space
+    pub fn inverse(&self) -> Self {
+        Self {
+            scale_x: 1.0 / self.scale_x,
+            scale_y: 1.0 / self.scale_y,
+            offset_x: -self.offset_x / self.scale_x,
+            offset_y: -self.offset_y / self.scale_y,
+            source_units: self.target_units,
+            target_units: self.source_units,
+        }
+    }
+
+    /// Get the transform that applies this transform first and the next one afterwards
+    pub fn then(&self, next: &Self) -> Self {
+        Self {
+            scale_x: self.scale_x * next.scale_x,
+            scale_y: self.scale_y * next.scale_y,
+            offset_x: self.offset_x * next.scale_x + next.offset_x,
+            offset_y: self.offset_y * next.scale_y + next.offset_y,
+            source_units: self.source_units,
+            target_units: next.target_units,
+        }
+    }
+
+    /// Get the unit of the points the transform is applied to
+    pub fn source_units(&self) -> Units {
+        self.source_units
+    }
+
+    /// Get the unit of the transformed points
+    pub fn target_units(&self) -> Units {
+        self.target_units
+    }
+
+    /// Transform a single point, e.g. a touch point
+    pub fn apply(&self, point: Point) -> Point {
+        (
+            point.0 * self.scale_x + self.offset_x,
+            point.1 * self.scale_y + self.offset_y,
+        )
+    }
+
+    /// Transform the points of a path
+    pub fn apply_to_path(&self, path: &[Point]) -> Vec<Point> {
+        path.iter().map(|&point| self.apply(point)).collect()
+    }
+
+    /// Transform the points of a timed path, their times stay the same
+    pub fn apply_to_timed_path(&self, path: &[TimedPoint]) -> Vec<TimedPoint> {
+        path.iter()
+            .map(|point| {
+                let (x, y) = self.apply((point.x, point.y));
+                TimedPoint { x, y, t: point.t }
+            })
+            .collect()
+    }
+
+    /// Transform the bounds, which stay ordered if an axis is flipped
+    pub fn apply_to_bounds(&self, bounds: &Bounds) -> Bounds {
+        let corners = [
+            self.apply((bounds.min_x, bounds.min_y)),
+            self.apply((bounds.max_x, bounds.max_y)),
+        ];
+        Bounds::from_points(&corners).expect("there are two corners")
+    }
+
+    /// Transform the keys and the bounds of a layout
+    /// The transformed layout is in the target unit of the transform. The corner radius of rounded keys is scaled by
+    /// the smaller scale of both axes.
+    pub fn apply_to_layout(&self, key_layout: &KeyLayout) -> Result<KeyLayout, LayoutError> {
+        let keys = key_layout
+            .keys()
+            .iter()
+            .map(|key| Key {
+                center: self.apply(key.center),
+                shape: key.shape.as_ref().map(|shape| self.apply_to_shape(shape)),
+                ..key.clone()
+            })
+            .collect();
+        let mut transformed = KeyLayout::new(key_layout.name(), keys)?
+            .with_units(self.target_units)
+            .with_bounds(self.apply_to_bounds(&key_layout.bounds()));
+        if let Some(folding) = key_layout.diacritic_folding() {
+            transformed = transformed.with_diacritic_folding(folding.clone());
+        }
+        Ok(transformed)
+    }
+
+    fn apply_to_shape(&self, shape: &KeyShape) -> KeyShape {
+        match shape {
+            KeyShape::Rect {
+                x,
+                y,
+                width,
+                height,
+            } => {
+                let bounds = self.apply_to_bounds(&Bounds {
+                    min_x: *x,
+                    min_y: *y,
+                    max_x: x + width,
+                    max_y: y + height,
+                });
+                KeyShape::Rect {
+                    x: bounds.min_x,
+                    y: bounds.min_y,
+                    width: bounds.width(),
+                    height: bounds.height(),
+                }
+            }
+            KeyShape::RoundedRect {
+                x,
+                y,
+                width,
+                height,
+                radius,
+            } => {
+                let bounds = self.apply_to_bounds(&Bounds {
+                    min_x: *x,
+                    min_y: *y,
+                    max_x: x + width,
+                    max_y: y + height,
+                });
+                KeyShape::RoundedRect {
+                    x: bounds.min_x,
+                    y: bounds.min_y,
+                    width: bounds.width(),
+                    height: bounds.height(),
+                    radius: radius * self.scale_x.abs().min(self.scale_y.abs()),
+                }
+            }
+            KeyShape::Polygon(corners) => KeyShape::Polygon(self.apply_to_path(corners)),
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use crate::error::MAX_NESTING_DEPTH;
+use crate::ImportError;
+
+/// An element of an XML document
+/// Text, comments and processing instructions are skipped, as layout files only use elements and attributes
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Element {
+    pub(crate) name: String,
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) children: Vec<Element>,
+    /// Line of the start tag, starting at 1
+    pub(crate) line: usize,
+}
+
+impl Element {
+    /// Get the value of an attribute by its name without the namespace prefix
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| local_name(attribute) == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get the name of the element without the namespace prefix
+    pub(crate) fn local_name(&self) -> &str {
+        local_name(&self.name)
+    }
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Parse an XML document and return its root element
+pub(crate) fn parse(source: &str) -> Result<Element, ImportError> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        pos: 0,
+        depth: 0,
+        line: 1,
+        line_pos: 0,
+    };
+    parser.skip_misc()?;
+    if parser.peek() != Some('<') {
+        return Err(parser.error("expected the root element"));
+    }
+    let root = parser.parse_element()?;
+    parser.skip_misc()?;
+    if parser.pos < parser.chars.len() {
+        return Err(parser.error("unexpected content after the root element"));
+    }
+    Ok(root)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    // Number of elements the parser is in
+    depth: usize,
+    // Line of the position line_pos, so the newlines before it don't have to be counted again for every element
+    line: usize,
+    line_pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn starts_with(&self, text: &str) -> bool {
+        let mut chars = self.chars[self.pos.min(self.chars.len())..].iter();
+        text.chars()
+            .all(|character| chars.next() == Some(&character))
+    }
+
+    // Line of the current position, counting the newlines between it and the last remembered line
+    fn line(&self) -> usize {
+        let pos = self.pos.min(self.chars.len());
+        let count_newlines =
+            |chars: &[char]| chars.iter().filter(|&&character| character == '\n').count();
+        if pos >= self.line_pos {
+            self.line + count_newlines(&self.chars[self.line_pos..pos])
+        } else {
+            self.line - count_newlines(&self.chars[pos..self.line_pos])
+        }
+    }
+
+    // Remember the line of the current position, so parsing stays linear in the size of the document
+    fn update_line(&mut self) -> usize {
+        self.line = self.line();
+        self.line_pos = self.pos.min(self.chars.len());
+        self.line
+    }
+
+    fn error(&self, message: &str) -> ImportError {
+        ImportError::Syntax {
+            line: self.line(),
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    // Skip everything up to and including the terminator
+    fn skip_past(&mut self, terminator: &str) -> Result<(), ImportError> {
+        while !self.starts_with(terminator) {
+            if self.pos >= self.chars.len() {
+                return Err(self.error(&format!("expected '{}'", terminator)));
+            }
+            self.pos += 1;
+        }
+        self.pos += terminator.chars().count();
+        Ok(())
+    }
+
+    // Skip whitespace, comments, processing instructions and the document type declaration
+    fn skip_misc(&mut self) -> Result<(), ImportError> {
+        loop {
+            self.skip_whitespace();
+            if self.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.starts_with("<!DOCTYPE") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<String, ImportError> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|character| !character.is_whitespace() && !"/>=<\"'".contains(character))
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("expected a name"));
+        }
+        Ok(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn parse_element(&mut self) -> Result<Element, ImportError> {
+        let line = self.update_line();
+        self.pos += 1;
+        let name = self.parse_name()?;
+        let mut attributes: Vec<(String, String)> = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some('/') if self.starts_with("/>") => {
+                    self.pos += 2;
+                    return Ok(Element {
+                        name,
+                        attributes,
+                        children: Vec::new(),
+                        line,
+                    });
+                }
+                Some('>') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => {
+                    let attribute = self.parse_name()?;
+                    self.skip_whitespace();
+                    if self.peek() != Some('=') {
+                        return Err(self.error(&format!("expected '=' after '{}'", attribute)));
+                    }
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    let value = self.parse_attribute_value()?;
+                    if attributes
+                        .iter()
+                        .any(|(existing, _)| *existing == attribute)
+                    {
+                        return Err(self.error(&format!("duplicate attribute '{}'", attribute)));
+                    }
+                    attributes.push((attribute, value));
+                }
+                None => return Err(self.error(&format!("unterminated tag '{}'", name))),
+            }
+        }
+
+        let mut children = Vec::new();
+        loop {
+            if self.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.starts_with("<![CDATA[") {
+                self.skip_past("]]>")?;
+            } else if self.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.starts_with("</") {
+                self.pos += 2;
+                let end_name = self.parse_name()?;
+                if end_name != name {
+                    return Err(self.error(&format!(
+                        "expected the end tag of '{}', but found '{}'",
+                        name, end_name
+                    )));
+                }
+                self.skip_whitespace();
+                if self.peek() != Some('>') {
+                    return Err(self.error("expected '>'"));
+                }
+                self.pos += 1;
+                return Ok(Element {
+                    name,
+                    attributes,
+                    children,
+                    line,
+                });
+            } else if self.peek() == Some('<') {
+                if self.depth >= MAX_NESTING_DEPTH {
+                    return Err(self.error(&format!(
+                        "elements are nested more than {} levels deep",
+                        MAX_NESTING_DEPTH
+                    )));
+                }
+                self.depth += 1;
+                children.push(self.parse_element()?);
+                self.depth -= 1;
+            } else if self.peek().is_some() {
+                // Text is not used by layouts
+                self.pos += 1;
+            } else {
+                return Err(self.error(&format!("missing the end tag of '{}'", name)));
+            }
+        }
+    }
+
+    fn parse_attribute_value(&mut self) -> Result<String, ImportError> {
+        let quote = match self.peek() {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => return Err(self.error("expected a quoted attribute value")),
+        };
+        self.pos += 1;
+        let mut value = String::new();
+        loop {
+            match self.peek() {
+                Some(character) if character == quote => {
+                    self.pos += 1;
+                    return Ok(value);
+                }
+                Some('&') => value.push(self.parse_reference()?),
+                Some('<') | None => return Err(self.error("unterminated attribute value")),
+                Some(character) => {
+                    value.push(character);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    // Parse an entity or character reference like "&amp;" or "&#x3C;"
+    fn parse_reference(&mut self) -> Result<char, ImportError> {
+        let start = self.pos;
+        self.skip_past(";")?;
+        let reference: String = self.chars[start + 1..self.pos - 1].iter().collect();
+        let character = match reference.as_str() {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => {
+                let code = match reference.strip_prefix("#x") {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => reference
+                        .strip_prefix('#')
+                        .and_then(|decimal| decimal.parse().ok()),
+                };
+                code.and_then(char::from_u32)
+            }
+        };
+        character.ok_or_else(|| {
+            self.pos = start;
+            self.error(&format!("unknown reference '&{};'", reference))
+        })
+    }
+}
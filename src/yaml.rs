@@ -0,0 +1,472 @@
+use crate::error::MAX_NESTING_DEPTH;
+use crate::ImportError;
+
+/// A node of a YAML document
+/// Only the subset of YAML used by layout files is supported: block and flow collections, plain and quoted
+/// scalars and comments. Anchors, tags and block scalars are not supported.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Yaml {
+    /// A scalar, which is empty for null values
+    Scalar(String),
+    Sequence(Vec<Yaml>),
+    /// The entries of a mapping in the order of the document
+    Mapping(Vec<(String, Yaml)>),
+}
+
+impl Yaml {
+    /// Get the value of a key if the node is a mapping
+    pub(crate) fn get(&self, key: &str) -> Option<&Yaml> {
+        match self {
+            Yaml::Mapping(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Yaml::Scalar(scalar) => Some(scalar),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_sequence(&self) -> Option<&[Yaml]> {
+        match self {
+            Yaml::Sequence(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+// A line of the document without its indentation and comment
+struct Line {
+    number: usize,
+    indent: usize,
+    content: String,
+}
+
+/// Parse a YAML document
+pub(crate) fn parse(source: &str) -> Result<Yaml, ImportError> {
+    let mut lines = Vec::new();
+    for (idx, raw_line) in source.lines().enumerate() {
+        let number = idx + 1;
+        let content = strip_comment(raw_line).trim_end();
+        let trimmed = content.trim_start_matches(' ');
+        if trimmed.starts_with('\t') {
+            return Err(syntax_error(number, "tabs can't be used for indentation"));
+        }
+        if trimmed.is_empty() || trimmed.starts_with('%') || trimmed == "---" {
+            continue;
+        }
+        if trimmed == "..." {
+            break;
+        }
+        lines.push(Line {
+            number,
+            indent: content.len() - trimmed.len(),
+            content: trimmed.to_string(),
+        });
+    }
+    if lines.is_empty() {
+        return Ok(Yaml::Scalar(String::new()));
+    }
+
+    let mut parser = Parser {
+        lines,
+        pos: 0,
+        depth: 0,
+    };
+    let document = parser.parse_node()?;
+    match parser.lines.get(parser.pos) {
+        Some(line) => Err(syntax_error(line.number, "unexpected indentation")),
+        None => Ok(document),
+    }
+}
+
+fn syntax_error(line: usize, message: &str) -> ImportError {
+    ImportError::Syntax {
+        line,
+        message: message.to_string(),
+    }
+}
+
+// Check if a quote at this position starts a quoted scalar, which is only the case at the beginning of a scalar
+fn starts_quote(prev_char: Option<char>) -> bool {
+    matches!(prev_char, None | Some(' ' | '[' | '{' | ','))
+}
+
+// Remove the comment from the end of a line
+fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    let mut prev_char: Option<char> = None;
+    let mut escaped = false;
+    for (idx, character) in line.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if character == '\\' => escaped = true,
+            Some(open) if character == open => quote = None,
+            Some(_) => {}
+            None if (character == '"' || character == '\'') && starts_quote(prev_char) => {
+                quote = Some(character)
+            }
+            None if character == '#' && matches!(prev_char, None | Some(' ')) => {
+                return &line[..idx];
+            }
+            None => {}
+        }
+        prev_char = Some(character);
+    }
+    line
+}
+
+// Find the colon that separates the key of a block mapping from its value
+fn find_mapping_colon(content: &str) -> Option<usize> {
+    if content.starts_with('{') || content.starts_with('[') {
+        return None;
+    }
+    let mut quote: Option<char> = None;
+    let mut prev_char: Option<char> = None;
+    let mut escaped = false;
+    let mut chars = content.char_indices().peekable();
+    while let Some((idx, character)) = chars.next() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if character == '\\' => escaped = true,
+            Some(open) if character == open => quote = None,
+            Some(_) => {}
+            None if (character == '"' || character == '\'') && starts_quote(prev_char) => {
+                quote = Some(character)
+            }
+            None if character == ':' && matches!(chars.peek(), None | Some((_, ' '))) => {
+                return Some(idx);
+            }
+            None => {}
+        }
+        prev_char = Some(character);
+    }
+    None
+}
+
+fn is_sequence_entry(content: &str) -> bool {
+    content == "-" || content.starts_with("- ")
+}
+
+// Parser of the block structure, which is defined by the indentation of the lines
+struct Parser {
+    lines: Vec<Line>,
+    pos: usize,
+    // Number of block collections the parser is in
+    depth: usize,
+}
+
+impl Parser {
+    // Parse the node that starts at the current line
+    fn parse_node(&mut self) -> Result<Yaml, ImportError> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(syntax_error(
+                self.lines[self.pos].number,
+                &format!(
+                    "collections are nested more than {} levels deep",
+                    MAX_NESTING_DEPTH
+                ),
+            ));
+        }
+        self.depth += 1;
+        let node = self.parse_node_content();
+        self.depth -= 1;
+        node
+    }
+
+    fn parse_node_content(&mut self) -> Result<Yaml, ImportError> {
+        let line = &self.lines[self.pos];
+        if is_sequence_entry(&line.content) {
+            self.parse_sequence(line.indent)
+        } else if find_mapping_colon(&line.content).is_some() {
+            self.parse_mapping(line.indent)
+        } else {
+            let (content, number) = (line.content.clone(), line.number);
+            self.pos += 1;
+            parse_flow(&content, number)
+        }
+    }
+
+    // Parse the value of an entry that starts on the next line, which must be indented more than the entry
+    fn parse_child(&mut self, parent_indent: usize) -> Result<Yaml, ImportError> {
+        match self.lines.get(self.pos) {
+            Some(line) if line.indent > parent_indent => self.parse_node(),
+            _ => Ok(Yaml::Scalar(String::new())),
+        }
+    }
+
+    fn parse_sequence(&mut self, indent: usize) -> Result<Yaml, ImportError> {
+        let mut items = Vec::new();
+        while let Some(line) = self.lines.get_mut(self.pos) {
+            if line.indent != indent || !is_sequence_entry(&line.content) {
+                break;
+            }
+            let rest = line.content[1..].trim_start_matches(' ');
+            if rest.is_empty() {
+                self.pos += 1;
+                items.push(self.parse_child(indent)?);
+            } else {
+                // The item starts on the same line as the dash, so it is parsed as if it was on a line of its own
+                line.indent += line.content.len() - rest.len();
+                line.content = rest.to_string();
+                items.push(self.parse_node()?);
+            }
+        }
+        Ok(Yaml::Sequence(items))
+    }
+
+    fn parse_mapping(&mut self, indent: usize) -> Result<Yaml, ImportError> {
+        let mut entries: Vec<(String, Yaml)> = Vec::new();
+        while let Some(line) = self.lines.get(self.pos) {
+            if line.indent != indent {
+                break;
+            }
+            let number = line.number;
+            let colon = find_mapping_colon(&line.content)
+                .ok_or_else(|| syntax_error(number, "expected a key of a mapping"))?;
+            let key = match parse_flow(line.content[..colon].trim_end(), number)? {
+                Yaml::Scalar(key) => key,
+                _ => return Err(syntax_error(number, "keys must be scalars")),
+            };
+            let rest = line.content[colon + 1..]
+                .trim_start_matches(' ')
+                .to_string();
+            self.pos += 1;
+
+            let value = if rest.starts_with('|') || rest.starts_with('>') {
+                return Err(syntax_error(number, "block scalars are not supported"));
+            } else if !rest.is_empty() {
+                parse_flow(&rest, number)?
+            } else {
+                match self.lines.get(self.pos) {
+                    // Sequences may have the same indentation as the key they belong to
+                    Some(next_line)
+                        if next_line.indent == indent && is_sequence_entry(&next_line.content) =>
+                    {
+                        self.parse_sequence(indent)?
+                    }
+                    _ => self.parse_child(indent)?,
+                }
+            };
+            if entries.iter().any(|(entry_key, _)| *entry_key == key) {
+                return Err(syntax_error(number, &format!("duplicate key '{}'", key)));
+            }
+            entries.push((key, value));
+        }
+        Ok(Yaml::Mapping(entries))
+    }
+}
+
+// Parse a node that is written on a single line, which is a scalar or a flow collection
+fn parse_flow(text: &str, line: usize) -> Result<Yaml, ImportError> {
+    let mut flow = Flow {
+        chars: text.chars().collect(),
+        pos: 0,
+        line,
+        depth: 0,
+    };
+    let node = flow.parse_value(false)?;
+    flow.skip_spaces();
+    if flow.pos < flow.chars.len() {
+        return Err(syntax_error(line, "unexpected characters after the value"));
+    }
+    Ok(node)
+}
+
+struct Flow {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    // Number of flow collections the parser is in
+    depth: usize,
+}
+
+impl Flow {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> ImportError {
+        syntax_error(self.line, message)
+    }
+
+    fn parse_value(&mut self, in_collection: bool) -> Result<Yaml, ImportError> {
+        self.skip_spaces();
+        match self.peek() {
+            Some(opening @ ('{' | '[')) => {
+                if self.depth >= MAX_NESTING_DEPTH {
+                    return Err(self.error(&format!(
+                        "collections are nested more than {} levels deep",
+                        MAX_NESTING_DEPTH
+                    )));
+                }
+                self.depth += 1;
+                let node = match opening {
+                    '{' => self.parse_mapping(),
+                    _ => self.parse_sequence(),
+                };
+                self.depth -= 1;
+                node
+            }
+            Some('"') => self.parse_double_quoted().map(Yaml::Scalar),
+            Some('\'') => self.parse_single_quoted().map(Yaml::Scalar),
+            Some('&' | '*' | '!') => Err(self.error("anchors, aliases and tags are not supported")),
+            _ => Ok(Yaml::Scalar(self.parse_plain(in_collection))),
+        }
+    }
+
+    // Plain scalars inside of flow collections end at indicators, outside of them they end at the end of the line
+    fn parse_plain(&mut self, in_collection: bool) -> String {
+        let start = self.pos;
+        while let Some(character) = self.peek() {
+            if in_collection {
+                let next_char = self.chars.get(self.pos + 1).copied();
+                let ends_key =
+                    character == ':' && matches!(next_char, None | Some(' ' | ',' | '}' | ']'));
+                if matches!(character, ',' | '}' | ']') || ends_key {
+                    break;
+                }
+            }
+            self.pos += 1;
+        }
+        self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    fn parse_mapping(&mut self) -> Result<Yaml, ImportError> {
+        self.pos += 1;
+        let mut entries: Vec<(String, Yaml)> = Vec::new();
+        loop {
+            self.skip_spaces();
+            if self.peek() == Some('}') {
+                self.pos += 1;
+                return Ok(Yaml::Mapping(entries));
+            }
+            let key = match self.parse_value(true)? {
+                Yaml::Scalar(key) => key,
+                _ => return Err(self.error("keys must be scalars")),
+            };
+            self.skip_spaces();
+            if self.peek() != Some(':') {
+                return Err(self.error(&format!("expected ':' after the key '{}'", key)));
+            }
+            self.pos += 1;
+            let value = self.parse_value(true)?;
+            if entries.iter().any(|(entry_key, _)| *entry_key == key) {
+                return Err(self.error(&format!("duplicate key '{}'", key)));
+            }
+            entries.push((key, value));
+            self.skip_spaces();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {}
+                _ => return Err(self.error("expected ',' or '}' in a mapping")),
+            }
+        }
+    }
+
+    fn parse_sequence(&mut self) -> Result<Yaml, ImportError> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_spaces();
+            if self.peek() == Some(']') {
+                self.pos += 1;
+                return Ok(Yaml::Sequence(items));
+            }
+            items.push(self.parse_value(true)?);
+            self.skip_spaces();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {}
+                _ => return Err(self.error("expected ',' or ']' in a sequence")),
+            }
+        }
+    }
+
+    fn parse_single_quoted(&mut self) -> Result<String, ImportError> {
+        self.pos += 1;
+        let mut scalar = String::new();
+        loop {
+            match self.peek() {
+                // A quote is escaped by doubling it
+                Some('\'') if self.chars.get(self.pos + 1) == Some(&'\'') => {
+                    scalar.push('\'');
+                    self.pos += 2;
+                }
+                Some('\'') => {
+                    self.pos += 1;
+                    return Ok(scalar);
+                }
+                Some(character) => {
+                    scalar.push(character);
+                    self.pos += 1;
+                }
+                None => return Err(self.error("unterminated single-quoted scalar")),
+            }
+        }
+    }
+
+    fn parse_double_quoted(&mut self) -> Result<String, ImportError> {
+        self.pos += 1;
+        let mut scalar = String::new();
+        loop {
+            let character = self
+                .peek()
+                .ok_or_else(|| self.error("unterminated double-quoted scalar"))?;
+            self.pos += 1;
+            match character {
+                '"' => return Ok(scalar),
+                '\\' => {
+                    let escape = self
+                        .peek()
+                        .ok_or_else(|| self.error("unterminated double-quoted scalar"))?;
+                    self.pos += 1;
+                    let escaped = match escape {
+                        '0' => '\0',
+                        't' => '\t',
+                        'n' => '\n',
+                        'r' => '\r',
+                        ' ' | '"' | '/' | '\\' => escape,
+                        'x' => self.parse_code_point(2)?,
+                        'u' => self.parse_code_point(4)?,
+                        'U' => self.parse_code_point(8)?,
+                        _ => {
+                            return Err(
+                                self.error(&format!("unknown escape sequence '\\{}'", escape))
+                            )
+                        }
+                    };
+                    scalar.push(escaped);
+                }
+                character => scalar.push(character),
+            }
+        }
+    }
+
+    fn parse_code_point(&mut self, no_digits: usize) -> Result<char, ImportError> {
+        let digits: String = self.chars.iter().skip(self.pos).take(no_digits).collect();
+        self.pos += no_digits;
+        if digits.chars().count() != no_digits {
+            return Err(self.error("incomplete escape sequence"));
+        }
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error(&format!("invalid code point '{}'", digits)))
+    }
+}